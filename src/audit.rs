@@ -0,0 +1,229 @@
+use anyhow::Result;
+use rmcp::model::{Implementation, JsonObject};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, SystemTime};
+use tokio::sync::mpsc;
+
+use crate::config::{AuditConfig, AuditOutput};
+
+/// Replacement for argument values matched by a redaction pointer.
+pub const REDACTED: &str = "[REDACTED]";
+
+// Records waiting for the writer before calls wait for it to catch up.
+const QUEUE_CAPACITY: usize = 1024;
+
+/// Who made a tool call, captured from the request before it is handled.
+#[derive(Debug, Clone, Default)]
+pub struct Caller {
+    /// `MCP-Session-Id` of a Streamable HTTP session.
+    pub session_id: Option<String>,
+    /// `clientInfo` sent by the client in `initialize`.
+    pub client: Option<Implementation>,
+    /// Authenticated principal, when the server requires credentials.
+    pub principal: Option<String>,
+}
+
+/// One finished tool call.
+pub struct AuditEvent<'a> {
+    pub caller: &'a Caller,
+    pub tool: &'a str,
+    /// `{"component", "function"}` or `{"channel"}`; `None` for unknown tools.
+    pub target: Option<serde_json::Value>,
+    pub arguments: &'a JsonObject,
+    pub duration: Duration,
+    /// `success`, or the error type recorded in telemetry.
+    pub outcome: &'a str,
+}
+
+/// Writes one JSON line per tool call to a file or stdout.
+///
+/// Records are written on a dedicated thread, so a slow file or a blocked
+/// stdout only delays calls once the queue is full. The thread exits once
+/// every clone is dropped and the queue is written.
+#[derive(Clone)]
+pub struct AuditLog {
+    records: mpsc::Sender<serde_json::Value>,
+    redact: Arc<Vec<String>>,
+}
+
+impl AuditLog {
+    /// Open the configured output, appending to an existing file.
+    pub fn open(server_name: &str, config: &AuditConfig) -> Result<(Self, AuditWriter)> {
+        let writer: Box<dyn Write + Send> = match &config.output {
+            AuditOutput::Stdout => Box::new(std::io::stdout()),
+            AuditOutput::File(path) => Box::new(
+                OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .map_err(|e| {
+                        anyhow::anyhow!(
+                            "Server '{server_name}': cannot open audit log '{}': {e}",
+                            path.display()
+                        )
+                    })?,
+            ),
+        };
+        Self::spawn(writer, config.redact.clone())
+    }
+
+    fn spawn(
+        mut writer: Box<dyn Write + Send>,
+        redact: Vec<String>,
+    ) -> Result<(Self, AuditWriter)> {
+        let (records, mut queue) = mpsc::channel::<serde_json::Value>(QUEUE_CAPACITY);
+        let thread = std::thread::Builder::new()
+            .name("audit-log".to_string())
+            .spawn(move || {
+                while let Some(record) = queue.blocking_recv() {
+                    let result = serde_json::to_writer(&mut writer, &record)
+                        .map_err(std::io::Error::from)
+                        .and_then(|_| writer.write_all(b"\n"))
+                        .and_then(|_| writer.flush());
+                    if let Err(e) = result {
+                        tracing::error!(
+                            tool = record["tool"].as_str(),
+                            "Failed to write audit record: {e}"
+                        );
+                    }
+                }
+            })?;
+        let log = Self {
+            records,
+            redact: Arc::new(redact),
+        };
+        Ok((log, AuditWriter { thread }))
+    }
+
+    /// Queue a record for a finished call, waiting while the queue is full.
+    /// Write failures are logged, not returned, so that auditing never
+    /// changes a call's result.
+    pub async fn record(&self, event: AuditEvent<'_>) {
+        let line = serde_json::json!({
+            "timestamp": humantime::format_rfc3339_millis(SystemTime::now()).to_string(),
+            "sessionId": event.caller.session_id,
+            "client": event.caller.client,
+            "principal": event.caller.principal,
+            "tool": event.tool,
+            "target": event.target,
+            "arguments": self.redacted(event.arguments),
+            "durationMs": event.duration.as_secs_f64() * 1000.0,
+            "outcome": event.outcome,
+        });
+        if self.records.send(line).await.is_err() {
+            tracing::error!(tool = event.tool, "Audit log writer has stopped");
+        }
+    }
+
+    // Arguments with each value matched by a redaction pointer replaced.
+    fn redacted(&self, arguments: &JsonObject) -> serde_json::Value {
+        let mut arguments = serde_json::Value::Object(arguments.clone());
+        for pointer in self.redact.iter() {
+            if let Some(value) = arguments.pointer_mut(pointer) {
+                *value = serde_json::Value::String(REDACTED.to_string());
+            }
+        }
+        arguments
+    }
+}
+
+/// The thread writing an audit log's records.
+pub struct AuditWriter {
+    thread: JoinHandle<()>,
+}
+
+impl AuditWriter {
+    /// Wait until every queued record is written. Returns once the log and
+    /// all of its clones have been dropped.
+    pub async fn finish(self) {
+        let thread = self.thread;
+        let _ = tokio::task::spawn_blocking(move || thread.join()).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    // Collects written bytes so tests can read them back.
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_record_redacts_arguments() {
+        let buffer = Buffer::default();
+        let (log, writer) = AuditLog::spawn(
+            Box::new(buffer.clone()),
+            vec![
+                "/password".to_string(),
+                "/card/number".to_string(),
+                "/missing".to_string(),
+            ],
+        )
+        .unwrap();
+        let caller = Caller {
+            session_id: Some("session-1".to_string()),
+            client: Some(Implementation::new("inspector", "1.0.0")),
+            principal: None,
+        };
+        let arguments = serde_json::json!({
+            "user": "ada",
+            "password": "hunter2",
+            "card": { "number": "4111111111111111", "expiry": "12/30" }
+        });
+
+        for outcome in ["success", "timeout"] {
+            log.record(AuditEvent {
+                caller: &caller,
+                tool: "checkout",
+                target: Some(serde_json::json!({ "component": "shop", "function": "checkout" })),
+                arguments: arguments.as_object().unwrap(),
+                duration: Duration::from_millis(12),
+                outcome,
+            })
+            .await;
+        }
+
+        drop(log);
+        writer.finish().await;
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<serde_json::Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        let record = &lines[0];
+        assert!(record["timestamp"].as_str().unwrap().ends_with('Z'));
+        assert_eq!(record["sessionId"], "session-1");
+        assert_eq!(record["client"]["name"], "inspector");
+        assert_eq!(record["principal"], serde_json::Value::Null);
+        assert_eq!(record["tool"], "checkout");
+        assert_eq!(record["target"]["component"], "shop");
+        assert_eq!(
+            record["arguments"],
+            serde_json::json!({
+                "user": "ada",
+                "password": REDACTED,
+                "card": { "number": REDACTED, "expiry": "12/30" }
+            })
+        );
+        assert_eq!(record["durationMs"], 12.0);
+        assert_eq!(record["outcome"], "success");
+        assert_eq!(lines[1]["outcome"], "timeout");
+    }
+}
@@ -0,0 +1,353 @@
+use anyhow::Result;
+use axum::{
+    extract::Request,
+    http::{HeaderMap, HeaderValue, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::sync::Arc;
+
+use crate::config::{AuthConfig, AuthScheme, SecretSource};
+use crate::oauth::{TokenVerifier, resource_metadata_url};
+
+/// The authenticated caller, inserted into request extensions by [`authenticate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Principal {
+    pub name: String,
+    /// Scopes granted by an OAuth access token; `None` for static credentials.
+    pub scopes: Option<Vec<String>>,
+}
+
+/// Credential policy for the MCP endpoint.
+#[derive(Clone)]
+pub struct AuthPolicy {
+    scheme: AuthScheme,
+    // (principal, secret) pairs
+    credentials: Arc<Vec<(String, String)>>,
+    verifier: Option<Arc<TokenVerifier>>,
+}
+
+impl AuthPolicy {
+    /// Build a policy from config, reading each secret from its env var or file.
+    pub fn from_config(server_name: &str, config: &AuthConfig) -> Result<Self> {
+        let mut credentials = Vec::new();
+        for credential in &config.credentials {
+            let secret = match &credential.secret {
+                SecretSource::Env(var) => std::env::var(var).map_err(|e| {
+                    anyhow::anyhow!(
+                        "Server '{server_name}': cannot read secret for principal '{}' from env var '{var}': {e}",
+                        credential.principal
+                    )
+                })?,
+                SecretSource::File(path) => std::fs::read_to_string(path).map_err(|e| {
+                    anyhow::anyhow!(
+                        "Server '{server_name}': cannot read secret for principal '{}' from file '{}': {e}",
+                        credential.principal,
+                        path.display()
+                    )
+                })?,
+            };
+            let secret = secret.trim().to_string();
+            if secret.is_empty() {
+                return Err(anyhow::anyhow!(
+                    "Server '{server_name}': secret for principal '{}' is empty",
+                    credential.principal
+                ));
+            }
+            credentials.push((credential.principal.clone(), secret));
+        }
+        let verifier = match &config.scheme {
+            AuthScheme::OAuth(oauth) => Some(Arc::new(TokenVerifier::new(server_name, oauth)?)),
+            _ => None,
+        };
+        Ok(Self {
+            scheme: config.scheme.clone(),
+            credentials: Arc::new(credentials),
+            verifier,
+        })
+    }
+
+    /// The token verifier, present only for the OAuth scheme.
+    pub fn verifier(&self) -> Option<&TokenVerifier> {
+        self.verifier.as_deref()
+    }
+
+    // Return the principal whose secret matches the presented credential, if any.
+    fn principal_for(&self, headers: &HeaderMap) -> Option<&str> {
+        let presented = match &self.scheme {
+            AuthScheme::Bearer => bearer_token(headers)?,
+            AuthScheme::ApiKey { header } => headers.get(header).and_then(|v| v.to_str().ok())?,
+            AuthScheme::OAuth(_) => return None,
+        };
+        self.credentials
+            .iter()
+            .find(|(_, secret)| constant_time_eq(secret.as_bytes(), presented.as_bytes()))
+            .map(|(principal, _)| principal.as_str())
+    }
+
+    // Verify an OAuth access token and return the principal it identifies.
+    async fn verify_token(&self, headers: &HeaderMap) -> Option<Principal> {
+        let verifier = self.verifier.as_ref()?;
+        let token = bearer_token(headers)?;
+        match verifier.verify(token).await {
+            Ok(verified) => Some(Principal {
+                name: verified.subject,
+                scopes: Some(verified.scopes),
+            }),
+            Err(e) => {
+                tracing::warn!("Rejected access token: {e}");
+                None
+            }
+        }
+    }
+
+    /// 403 response for a token that lacks the `scopes` a request needs,
+    /// with an `insufficient_scope` challenge (RFC 6750 section 3.1).
+    pub fn insufficient_scope(&self, scopes: &[String]) -> Response {
+        let mut challenge = format!(
+            "Bearer error=\"insufficient_scope\", scope=\"{}\"",
+            scopes.join(" ")
+        );
+        if let AuthScheme::OAuth(oauth) = &self.scheme {
+            challenge.push_str(&format!(
+                ", resource_metadata=\"{}\"",
+                resource_metadata_url(&oauth.resource)
+            ));
+        }
+        let mut response = (StatusCode::FORBIDDEN, "Forbidden: insufficient scope").into_response();
+        if let Ok(value) = HeaderValue::from_str(&challenge) {
+            response
+                .headers_mut()
+                .insert(header::WWW_AUTHENTICATE, value);
+        }
+        response
+    }
+
+    fn challenge(&self) -> HeaderValue {
+        match &self.scheme {
+            AuthScheme::Bearer => HeaderValue::from_static("Bearer realm=\"mcp\""),
+            AuthScheme::ApiKey { header } => {
+                HeaderValue::from_str(&format!("ApiKey realm=\"mcp\", header=\"{header}\""))
+                    .unwrap_or_else(|_| HeaderValue::from_static("ApiKey realm=\"mcp\""))
+            }
+            AuthScheme::OAuth(oauth) => HeaderValue::from_str(&format!(
+                "Bearer error=\"invalid_token\", resource_metadata=\"{}\"",
+                resource_metadata_url(&oauth.resource)
+            ))
+            .unwrap_or_else(|_| HeaderValue::from_static("Bearer error=\"invalid_token\"")),
+        }
+    }
+}
+
+// Extract the token from an `Authorization: Bearer <token>` header.
+fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| {
+            v.split_once(' ')
+                .filter(|(scheme, _)| scheme.eq_ignore_ascii_case("bearer"))
+                .map(|(_, token)| token.trim())
+        })
+}
+
+/// Validate the request's credential.
+///
+/// - Credential matches a configured secret => allow, recording the [`Principal`]
+/// - OAuth access token verifies => allow, recording the [`Principal`] and its scopes
+/// - Missing or unknown credential => 401 Unauthorized with `WWW-Authenticate`
+pub async fn authenticate(
+    axum::extract::State(policy): axum::extract::State<AuthPolicy>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let principal = match policy.principal_for(request.headers()) {
+        Some(name) => Some(Principal {
+            name: name.to_string(),
+            scopes: None,
+        }),
+        None => policy.verify_token(request.headers()).await,
+    };
+    if let Some(principal) = principal {
+        request.extensions_mut().insert(principal);
+        return next.run(request).await;
+    }
+    tracing::warn!("Rejected request with missing or invalid credentials");
+    let mut response = (StatusCode::UNAUTHORIZED, "Unauthorized").into_response();
+    response
+        .headers_mut()
+        .insert(header::WWW_AUTHENTICATE, policy.challenge());
+    response
+}
+
+// Compare without short-circuiting on the first differing byte.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::CredentialConfig;
+    use std::io::Write as _;
+
+    fn policy(scheme: AuthScheme, credentials: &[(&str, &str)]) -> AuthPolicy {
+        AuthPolicy {
+            scheme,
+            credentials: Arc::new(
+                credentials
+                    .iter()
+                    .map(|(p, s)| (p.to_string(), s.to_string()))
+                    .collect(),
+            ),
+            verifier: None,
+        }
+    }
+
+    fn headers(pairs: &[(&'static str, &'static str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (name, value) in pairs {
+            map.insert(*name, HeaderValue::from_static(value));
+        }
+        map
+    }
+
+    #[test]
+    fn test_bearer_token() {
+        let policy = policy(
+            AuthScheme::Bearer,
+            &[("ci", "s3cret"), ("alice", "hunter2")],
+        );
+        assert_eq!(
+            policy.principal_for(&headers(&[("authorization", "Bearer hunter2")])),
+            Some("alice")
+        );
+        assert_eq!(
+            policy.principal_for(&headers(&[("authorization", "bearer s3cret")])),
+            Some("ci")
+        );
+        assert_eq!(
+            policy.principal_for(&headers(&[("authorization", "Bearer wrong")])),
+            None
+        );
+        assert_eq!(
+            policy.principal_for(&headers(&[("authorization", "Basic s3cret")])),
+            None
+        );
+        assert_eq!(policy.principal_for(&HeaderMap::new()), None);
+    }
+
+    #[test]
+    fn test_api_key_header() {
+        let policy = policy(
+            AuthScheme::ApiKey {
+                header: "X-API-Key".to_string(),
+            },
+            &[("ci", "s3cret")],
+        );
+        assert_eq!(
+            policy.principal_for(&headers(&[("x-api-key", "s3cret")])),
+            Some("ci")
+        );
+        assert_eq!(
+            policy.principal_for(&headers(&[("authorization", "Bearer s3cret")])),
+            None
+        );
+    }
+
+    #[test]
+    fn test_secret_from_file_is_trimmed() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(file, "  from-file  ").unwrap();
+        let config = AuthConfig {
+            scheme: AuthScheme::Bearer,
+            credentials: vec![CredentialConfig {
+                principal: "ops".to_string(),
+                secret: SecretSource::File(file.path().to_path_buf()),
+            }],
+        };
+        let policy = AuthPolicy::from_config("mcp", &config).unwrap();
+        assert_eq!(
+            policy.principal_for(&headers(&[("authorization", "Bearer from-file")])),
+            Some("ops")
+        );
+    }
+
+    #[test]
+    fn test_missing_env_secret() {
+        let config = AuthConfig {
+            scheme: AuthScheme::Bearer,
+            credentials: vec![CredentialConfig {
+                principal: "ci".to_string(),
+                secret: SecretSource::Env("TOOLBELT_TEST_UNSET_SECRET".to_string()),
+            }],
+        };
+        let err = AuthPolicy::from_config("mcp", &config)
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(
+            err.contains("TOOLBELT_TEST_UNSET_SECRET"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn test_oauth_challenge_points_to_metadata() {
+        let policy = policy(
+            AuthScheme::OAuth(crate::config::OAuthConfig {
+                issuer: "https://idp.example.com".to_string(),
+                audience: "https://mcp.example.com/mcp".to_string(),
+                jwks: crate::config::JwksSource::Url(
+                    "https://idp.example.com/jwks.json".to_string(),
+                ),
+                resource: "https://mcp.example.com/mcp".to_string(),
+                authorization_servers: vec!["https://idp.example.com".to_string()],
+            }),
+            &[],
+        );
+        assert_eq!(
+            policy.challenge(),
+            "Bearer error=\"invalid_token\", \
+             resource_metadata=\"https://mcp.example.com/.well-known/oauth-protected-resource\""
+        );
+        assert_eq!(
+            policy.principal_for(&headers(&[("authorization", "Bearer anything")])),
+            None
+        );
+    }
+
+    #[test]
+    fn test_insufficient_scope_challenge() {
+        let policy = policy(
+            AuthScheme::OAuth(crate::config::OAuthConfig {
+                issuer: "https://idp.example.com".to_string(),
+                audience: "https://mcp.example.com/mcp".to_string(),
+                jwks: crate::config::JwksSource::Url(
+                    "https://idp.example.com/jwks.json".to_string(),
+                ),
+                resource: "https://mcp.example.com/mcp".to_string(),
+                authorization_servers: vec!["https://idp.example.com".to_string()],
+            }),
+            &[],
+        );
+        let response =
+            policy.insufficient_scope(&["users:read".to_string(), "users:write".to_string()]);
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert_eq!(
+            response.headers()[header::WWW_AUTHENTICATE],
+            "Bearer error=\"insufficient_scope\", scope=\"users:read users:write\", \
+             resource_metadata=\"https://mcp.example.com/.well-known/oauth-protected-resource\""
+        );
+    }
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"abc", b"abc"));
+        assert!(!constant_time_eq(b"abc", b"abd"));
+        assert!(!constant_time_eq(b"abc", b"abcd"));
+    }
+}
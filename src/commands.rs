@@ -0,0 +1,362 @@
+use anyhow::Result;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+use rmcp::model::{CallToolResult, JsonObject, Tool};
+use std::sync::Arc;
+
+use crate::auth::AuthPolicy;
+use crate::config::{self, McpServerConfig, Transport};
+use crate::origin::OriginPolicy;
+use crate::server::McpServer;
+use crate::service::{McpService, check_tools, resolve_prompts, resolve_resources, resolve_tools};
+
+/// Load definitions and resolve every MCP server without binding any ports.
+///
+/// Returns all errors found: invalid server definitions, unknown components
+/// or functions, invalid schemas, unusable auth settings and port conflicts.
+pub async fn validate(paths: &[PathBuf]) -> Vec<anyhow::Error> {
+    let (runtime, mut server_configs, mut errors) = match McpService::check_definitions(paths).await
+    {
+        Ok(checked) => checked,
+        Err(e) => return vec![e],
+    };
+    let invoker = runtime.invoker();
+    // Servers with invalid definitions are reported above; the rest are still checked.
+    if server_configs.is_empty() && errors.is_empty() {
+        server_configs.push(config::default_server());
+    }
+
+    for server_config in &server_configs {
+        errors.extend(check_tools(server_config, &*invoker));
+        if let Err(e) = resolve_resources(server_config, &*invoker) {
+            errors.push(e);
+        }
+        if let Err(e) = resolve_prompts(server_config, &*invoker) {
+            errors.push(e);
+        }
+        if let Some(auth) = &server_config.auth
+            && let Err(e) = AuthPolicy::from_config(&server_config.name, auth)
+        {
+            errors.push(e);
+        }
+    }
+    errors.extend(check_listeners(&server_configs));
+    errors
+}
+
+/// Call one tool through the same path as `tools/call`, without any MCP client.
+///
+/// Arguments are validated against the input schema and the result is
+/// converted exactly as for a client. Scopes are not checked. With no
+/// `server`, the tool is looked up in every server that resolves and must be
+/// unambiguous.
+pub async fn call_tool(
+    paths: &[PathBuf],
+    server: Option<&str>,
+    tool_name: &str,
+    arguments: JsonObject,
+) -> Result<CallToolResult> {
+    let (runtime, mut server_configs) = McpService::load_definitions(paths).await?;
+    let invoker = runtime.invoker();
+    if server_configs.is_empty() {
+        server_configs.push(config::default_server());
+    }
+
+    let mut matches = Vec::new();
+    for server_config in &server_configs {
+        if server.is_some_and(|name| name != server_config.name) {
+            continue;
+        }
+        // A server that fails to resolve only matters if it is the one asked
+        // for; otherwise the tool may still be found in another server.
+        let tools = match resolve_tools(server_config, &*invoker) {
+            Ok(tools) => tools,
+            Err(e) if server.is_some() => return Err(e),
+            Err(e) => {
+                tracing::warn!(server_name = server_config.name, "Skipping server: {e}");
+                continue;
+            }
+        };
+        if tools.contains_key(tool_name) {
+            matches.push((server_config.name.as_str(), tools));
+        }
+    }
+    let tools = match matches.len() {
+        0 => {
+            return Err(match server {
+                Some(name) if server_configs.iter().all(|s| s.name != name) => {
+                    anyhow::anyhow!("No MCP server named '{name}'")
+                }
+                _ => anyhow::anyhow!("Tool not found: {tool_name}"),
+            });
+        }
+        1 => matches.remove(0).1,
+        _ => {
+            let names: Vec<_> = matches.iter().map(|(name, _)| *name).collect();
+            return Err(anyhow::anyhow!(
+                "Tool '{tool_name}' is published by servers {names:?}; choose one with --server"
+            ));
+        }
+    };
+
+    let server = McpServer::new(
+        tools,
+        Arc::clone(&invoker),
+        runtime.publisher(),
+        SocketAddr::from(([127, 0, 0, 1], 0)),
+        OriginPolicy::from_config(None, "127.0.0.1"),
+        None,
+    );
+    Ok(server.invoke_tool(tool_name, &arguments).await)
+}
+
+/// The tools each MCP server publishes, exactly as clients see them.
+pub struct ToolListing {
+    servers: Vec<(String, Vec<Tool>)>,
+}
+
+impl ToolListing {
+    /// Load definitions and resolve the tools of every MCP server, or only `server`.
+    pub async fn resolve(paths: &[PathBuf], server: Option<&str>) -> Result<Self> {
+        let (runtime, mut server_configs) = McpService::load_definitions(paths).await?;
+        let invoker = runtime.invoker();
+        if server_configs.is_empty() {
+            server_configs.push(config::default_server());
+        }
+        if let Some(name) = server
+            && !server_configs.iter().any(|s| s.name == name)
+        {
+            return Err(anyhow::anyhow!("No MCP server named '{name}'"));
+        }
+
+        let mut servers = Vec::new();
+        for server_config in &server_configs {
+            if server.is_some_and(|name| name != server_config.name) {
+                continue;
+            }
+            let tools = resolve_tools(server_config, &*invoker)?
+                .into_values()
+                .map(|resolved| resolved.tool)
+                .collect();
+            servers.push((server_config.name.clone(), tools));
+        }
+        Ok(Self { servers })
+    }
+
+    /// Tool JSON keyed by server name, as returned by `tools/list`.
+    pub fn to_json(&self) -> serde_json::Value {
+        self.servers
+            .iter()
+            .map(|(name, tools)| (name.clone(), serde_json::json!(tools)))
+            .collect::<serde_json::Map<_, _>>()
+            .into()
+    }
+
+    /// One row per tool with its parameters, required ones marked with `*`.
+    pub fn to_table(&self) -> String {
+        let mut rows = vec![[
+            "SERVER".to_string(),
+            "TOOL".to_string(),
+            "PARAMETERS".to_string(),
+            "OUTPUT".to_string(),
+            "DESCRIPTION".to_string(),
+        ]];
+        for (server_name, tools) in &self.servers {
+            for tool in tools {
+                let required: Vec<&str> = tool
+                    .input_schema
+                    .get("required")
+                    .and_then(|r| r.as_array())
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|r| r.as_str())
+                    .collect();
+                let parameters: Vec<String> = tool
+                    .input_schema
+                    .get("properties")
+                    .and_then(|p| p.as_object())
+                    .into_iter()
+                    .flat_map(|p| p.keys())
+                    .map(|name| {
+                        if required.contains(&name.as_str()) {
+                            format!("{name}*")
+                        } else {
+                            name.clone()
+                        }
+                    })
+                    .collect();
+                let description = tool.description.as_deref().unwrap_or_default();
+                rows.push([
+                    server_name.clone(),
+                    tool.name.to_string(),
+                    parameters.join(", "),
+                    if tool.output_schema.is_some() {
+                        "structured"
+                    } else {
+                        "text"
+                    }
+                    .to_string(),
+                    description.lines().next().unwrap_or_default().to_string(),
+                ]);
+            }
+        }
+
+        let mut widths = [0; 5];
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+        let mut table = String::new();
+        for row in &rows {
+            let line: Vec<String> = row
+                .iter()
+                .zip(widths)
+                .map(|(cell, width)| format!("{cell:width$}"))
+                .collect();
+            table.push_str(line.join("  ").trim_end());
+            table.push('\n');
+        }
+        table
+    }
+}
+
+// Check that listen addresses parse, that no two listeners (including
+// metrics ports) share a port and that at most one server uses stdio.
+fn check_listeners(server_configs: &[McpServerConfig]) -> Vec<anyhow::Error> {
+    let mut errors = Vec::new();
+    let mut listeners: HashMap<u16, Vec<(&str, SocketAddr)>> = HashMap::new();
+    for server_config in server_configs {
+        let addr =
+            match format!("{}:{}", server_config.host, server_config.port).parse::<SocketAddr>() {
+                Ok(addr) => addr,
+                Err(e) => {
+                    errors.push(anyhow::anyhow!(
+                        "Server '{}': invalid address '{}:{}': {e}",
+                        server_config.name,
+                        server_config.host,
+                        server_config.port,
+                    ));
+                    continue;
+                }
+            };
+        let metrics_port = server_config.prometheus.as_ref().and_then(|p| p.port);
+        let ports = (server_config.transport != Transport::Stdio)
+            .then_some(server_config.port)
+            .into_iter()
+            .chain(metrics_port);
+        for port in ports {
+            listeners
+                .entry(port)
+                .or_default()
+                .push((&server_config.name, SocketAddr::new(addr.ip(), port)));
+        }
+    }
+    let mut ports: Vec<_> = listeners.into_iter().collect();
+    ports.sort_by_key(|(port, _)| *port);
+    for (port, servers) in ports {
+        for (i, (name, addr)) in servers.iter().enumerate() {
+            // An unspecified address binds every interface, so it conflicts with any other.
+            if let Some((other, _)) = servers[..i].iter().find(|(_, other)| {
+                other.ip() == addr.ip() || other.ip().is_unspecified() || addr.ip().is_unspecified()
+            }) {
+                errors.push(anyhow::anyhow!(
+                    "Server '{name}': port {port} is already used by server '{other}'"
+                ));
+            }
+        }
+    }
+    let stdio_servers = server_configs
+        .iter()
+        .filter(|s| s.transport == Transport::Stdio)
+        .count();
+    if stdio_servers > 1 {
+        errors.push(anyhow::anyhow!(
+            "Only one MCP server can use transport 'stdio', found {stdio_servers}"
+        ));
+    }
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server(name: &str, host: &str, port: u16) -> McpServerConfig {
+        McpServerConfig {
+            name: name.to_string(),
+            host: host.to_string(),
+            port,
+            ..config::default_server()
+        }
+    }
+
+    #[test]
+    fn test_tool_table() {
+        let tool = |name: &str, description: &str| {
+            Tool::new(
+                name.to_string(),
+                description.to_string(),
+                serde_json::json!({
+                    "type": "object",
+                    "properties": { "a": { "type": "number" }, "b": { "type": "number" } },
+                    "required": ["a"]
+                })
+                .as_object()
+                .unwrap()
+                .clone(),
+            )
+        };
+        let listing = ToolListing {
+            servers: vec![(
+                "mcp".to_string(),
+                vec![
+                    tool("add", "Add two numbers.\nReturns their sum."),
+                    tool("subtract", "Subtract b from a."),
+                ],
+            )],
+        };
+        assert_eq!(
+            listing.to_table(),
+            "SERVER  TOOL      PARAMETERS  OUTPUT  DESCRIPTION\n\
+             mcp     add       a*, b       text    Add two numbers.\n\
+             mcp     subtract  a*, b       text    Subtract b from a.\n"
+        );
+        assert_eq!(listing.to_json()["mcp"][1]["name"], "subtract");
+        assert_eq!(
+            listing.to_json()["mcp"][0]["inputSchema"]["required"][0],
+            "a"
+        );
+    }
+
+    #[test]
+    fn test_port_conflicts() {
+        let errors = check_listeners(&[
+            server("a", "127.0.0.1", 3001),
+            server("b", "127.0.0.1", 3001),
+            server("c", "127.0.0.2", 3002),
+            server("d", "0.0.0.0", 3002),
+            server("e", "127.0.0.1", 3003),
+            server("f", "localhost", 3004),
+            McpServerConfig {
+                prometheus: Some(config::PrometheusConfig {
+                    path: "/metrics".to_string(),
+                    port: Some(3003),
+                }),
+                ..server("g", "127.0.0.1", 3005)
+            },
+        ]);
+        let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            messages,
+            [
+                "Server 'f': invalid address 'localhost:3004': invalid socket address syntax",
+                "Server 'b': port 3001 is already used by server 'a'",
+                "Server 'd': port 3002 is already used by server 'c'",
+                "Server 'g': port 3003 is already used by server 'e'",
+            ]
+        );
+    }
+}
@@ -1,5 +1,7 @@
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use anyhow::Result;
 
@@ -7,6 +9,8 @@ use composable_runtime::{
     CategoryClaim, Condition, ConfigHandler, Operator, PropertyMap, Selector,
 };
 
+use crate::oauth::PROTECTED_RESOURCE_METADATA_PATH;
+
 // Default component selector for auto-discovery: top-level components only.
 const DEFAULT_COMPONENT_SELECTOR: &str = "!dependents";
 
@@ -16,6 +20,12 @@ pub enum ToolTarget {
     Component {
         component: String,
         function: String,
+        /// Narrows or documents the WIT-derived output schema.
+        output_schema: Option<serde_json::Value>,
+        /// Values for parameters the caller omits.
+        defaults: serde_json::Map<String, serde_json::Value>,
+        /// Values for parameters hidden from the caller.
+        fixed: serde_json::Map<String, serde_json::Value>,
     },
     Channel {
         channel: String,
@@ -24,25 +34,291 @@ pub enum ToolTarget {
     },
 }
 
+/// Hints describing a tool's behavior, carried onto the MCP tool's annotations.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ToolAnnotationsConfig {
+    pub title: Option<String>,
+    pub read_only_hint: Option<bool>,
+    pub destructive_hint: Option<bool>,
+    pub idempotent_hint: Option<bool>,
+    pub open_world_hint: Option<bool>,
+}
+
+// Hint keys shared by tool `annotations` tables and component labels.
+const ANNOTATION_HINTS: [&str; 4] = [
+    "read-only-hint",
+    "destructive-hint",
+    "idempotent-hint",
+    "open-world-hint",
+];
+
+impl ToolAnnotationsConfig {
+    /// Read hints from component labels, e.g. `read-only-hint = "true"`.
+    pub fn from_labels(
+        component_name: &str,
+        labels: &HashMap<String, String>,
+    ) -> Result<Option<Self>> {
+        let mut annotations = Self::default();
+        for key in ANNOTATION_HINTS {
+            let Some(value) = labels.get(key) else {
+                continue;
+            };
+            let hint = match value.as_str() {
+                "true" => true,
+                "false" => false,
+                _ => {
+                    return Err(anyhow::anyhow!(
+                        "Component '{component_name}': label '{key}' must be \"true\" or \"false\", got '{value}'"
+                    ));
+                }
+            };
+            *annotations.hint_mut(key) = Some(hint);
+        }
+        annotations
+            .validate()
+            .map_err(|e| anyhow::anyhow!("Component '{component_name}': {e}"))?;
+        Ok((annotations != Self::default()).then_some(annotations))
+    }
+
+    /// Fill any unset fields from `fallback`.
+    pub fn or(self, fallback: Self) -> Self {
+        Self {
+            title: self.title.or(fallback.title),
+            read_only_hint: self.read_only_hint.or(fallback.read_only_hint),
+            destructive_hint: self.destructive_hint.or(fallback.destructive_hint),
+            idempotent_hint: self.idempotent_hint.or(fallback.idempotent_hint),
+            open_world_hint: self.open_world_hint.or(fallback.open_world_hint),
+        }
+    }
+
+    fn hint_mut(&mut self, key: &str) -> &mut Option<bool> {
+        match key {
+            "read-only-hint" => &mut self.read_only_hint,
+            "destructive-hint" => &mut self.destructive_hint,
+            "idempotent-hint" => &mut self.idempotent_hint,
+            "open-world-hint" => &mut self.open_world_hint,
+            _ => unreachable!("unknown annotation hint '{key}'"),
+        }
+    }
+
+    /// Reject hints that contradict each other. Destructive and idempotent hints
+    /// only apply to tools that modify their environment.
+    pub fn validate(&self) -> std::result::Result<(), String> {
+        if self.read_only_hint == Some(true) {
+            for (key, hint) in [
+                ("destructive-hint", self.destructive_hint),
+                ("idempotent-hint", self.idempotent_hint),
+            ] {
+                if hint.is_some() {
+                    return Err(format!(
+                        "'{key}' cannot be combined with 'read-only-hint = true'"
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
 /// Parsed tool within an MCP server.
 #[derive(Debug, Clone)]
 pub struct ToolConfig {
     pub name: String,
     pub target: ToolTarget,
     pub description: Option<String>,
+    /// OAuth scopes the caller's access token must carry.
+    pub scopes: Vec<String>,
+    /// Overrides the server's default invocation timeout.
+    pub timeout: Option<Duration>,
+    /// Overrides the server's default rate limit.
+    pub rate_limit: Option<RateLimitConfig>,
+    pub annotations: Option<ToolAnnotationsConfig>,
+}
+
+/// Token bucket applied to each tool in each session: `requests` calls are
+/// allowed `per` interval, with up to `burst` in quick succession.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimitConfig {
+    pub requests: u32,
+    pub per: Duration,
+    pub burst: u32,
+}
+
+/// Parsed resource within an MCP server, backed by a component function.
+#[derive(Debug, Clone)]
+pub struct ResourceConfig {
+    pub name: String,
+    pub uri: String,
+    pub component: String,
+    pub function: String,
+    pub description: Option<String>,
+    pub mime_type: Option<String>,
+}
+
+/// Parsed prompt within an MCP server, backed by a component function.
+#[derive(Debug, Clone)]
+pub struct PromptConfig {
+    pub name: String,
+    pub component: String,
+    pub function: String,
+    pub description: Option<String>,
+}
+
+/// How an MCP server is exposed to clients.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transport {
+    /// Streamable HTTP on `host:port` at `/mcp`.
+    StreamableHttp,
+    /// JSON-RPC over the process's stdin/stdout, for clients that launch the server
+    /// as a subprocess.
+    Stdio,
+}
+
+/// Where a credential's secret value is read from at startup.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SecretSource {
+    Env(String),
+    File(PathBuf),
+}
+
+/// A static credential and the principal it authenticates.
+#[derive(Debug, Clone)]
+pub struct CredentialConfig {
+    pub principal: String,
+    pub secret: SecretSource,
+}
+
+/// Where the JSON Web Key Set for verifying access tokens comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JwksSource {
+    File(PathBuf),
+    Url(String),
+}
+
+/// OAuth 2.1 resource server settings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OAuthConfig {
+    /// Expected `iss` claim.
+    pub issuer: String,
+    /// Expected `aud` claim.
+    pub audience: String,
+    pub jwks: JwksSource,
+    /// Resource identifier advertised in the protected resource metadata.
+    pub resource: String,
+    pub authorization_servers: Vec<String>,
+}
+
+/// How clients present credentials.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuthScheme {
+    /// `Authorization: Bearer <token>`
+    Bearer,
+    /// API key in the named header.
+    ApiKey { header: String },
+    /// `Authorization: Bearer <jwt>` issued by an OAuth authorization server.
+    OAuth(OAuthConfig),
+}
+
+/// Parsed `auth` section of an MCP server.
+#[derive(Debug, Clone)]
+pub struct AuthConfig {
+    pub scheme: AuthScheme,
+    pub credentials: Vec<CredentialConfig>,
+}
+
+/// Where the Prometheus scrape endpoint is served.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrometheusConfig {
+    pub path: String,
+    /// Serve on this port instead of alongside the MCP endpoint.
+    pub port: Option<u16>,
+}
+
+/// Paths of the HTTP liveness and readiness probes. `None` disables a probe.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HealthConfig {
+    pub liveness_path: Option<String>,
+    pub readiness_path: Option<String>,
+}
+
+impl Default for HealthConfig {
+    fn default() -> Self {
+        Self {
+            liveness_path: Some("/healthz".to_string()),
+            readiness_path: Some("/readyz".to_string()),
+        }
+    }
+}
+
+/// Where audit records are written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuditOutput {
+    Stdout,
+    /// Appended to, and created if missing.
+    File(PathBuf),
+}
+
+/// JSON-lines audit log of tool invocations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditConfig {
+    pub output: AuditOutput,
+    /// JSON pointers into the arguments whose values are replaced before writing.
+    pub redact: Vec<String>,
 }
 
+/// Case style applied to each part of a discovered tool's name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NameCase {
+    /// Keep each part as written, replacing disallowed characters with `_`.
+    Preserve,
+    /// `my_function`
+    Snake,
+    /// `my-function`
+    Kebab,
+    /// `myFunction`
+    Camel,
+}
+
+/// How selector-discovered tools are named.
+///
+/// A name is the prefix (if any), component name and function key, each
+/// converted to `case` and joined by `separator`, then truncated to `max_length`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolNamingConfig {
+    pub prefix: Option<String>,
+    pub separator: String,
+    pub case: NameCase,
+    pub max_length: usize,
+}
+
+/// Longest tool name accepted by common MCP clients.
+pub const MAX_TOOL_NAME_LENGTH: usize = 64;
+
 /// Parsed MCP server definition.
 #[derive(Debug, Clone)]
 pub struct McpServerConfig {
     pub name: String,
+    pub transport: Transport,
     pub host: String,
     pub port: u16,
     pub allowed_origins: Option<Vec<String>>,
+    pub auth: Option<AuthConfig>,
     pub component_selector: Option<Selector>,
+    /// Naming for selector-discovered tools; `None` keeps `component.function`.
+    pub tool_naming: Option<ToolNamingConfig>,
     pub tools: Vec<ToolConfig>,
+    pub resources: Vec<ResourceConfig>,
+    pub prompts: Vec<PromptConfig>,
+    pub page_size: Option<usize>,
+    /// Default invocation timeout for tools without their own.
+    pub timeout: Option<Duration>,
+    /// Default rate limit for tools without their own.
+    pub rate_limit: Option<RateLimitConfig>,
     pub otlp_endpoint: Option<String>,
     pub otlp_protocol: String,
+    pub prometheus: Option<PrometheusConfig>,
+    pub health: HealthConfig,
+    pub audit: Option<AuditConfig>,
 }
 
 pub type SharedConfig = Arc<Mutex<Vec<McpServerConfig>>>;
@@ -55,16 +331,27 @@ pub fn shared_config() -> SharedConfig {
 pub fn default_server() -> McpServerConfig {
     McpServerConfig {
         name: "mcp".to_string(),
+        transport: Transport::StreamableHttp,
         host: "127.0.0.1".to_string(),
         port: 3001,
         allowed_origins: None,
+        auth: None,
         component_selector: Some(
             Selector::parse(DEFAULT_COMPONENT_SELECTOR)
                 .expect("default component selector is valid"),
         ),
+        tool_naming: None,
         tools: Vec::new(),
+        resources: Vec::new(),
+        prompts: Vec::new(),
+        page_size: None,
+        timeout: None,
+        rate_limit: None,
         otlp_endpoint: None,
         otlp_protocol: "grpc".to_string(),
+        prometheus: None,
+        health: HealthConfig::default(),
+        audit: None,
     }
 }
 
@@ -97,13 +384,26 @@ impl ConfigHandler for McpServerConfigHandler {
             "server",
             [
                 "type",
+                "transport",
                 "host",
                 "port",
                 "allowed-origins",
+                "auth",
                 "component-selector",
+                "tool-naming",
+                "page-size",
+                "timeout",
+                "rate-limit",
                 "otlp-endpoint",
                 "otlp-protocol",
+                "metrics-path",
+                "metrics-port",
+                "health-path",
+                "ready-path",
+                "audit",
                 "tool",
+                "resource",
+                "prompt",
             ]
             .as_slice(),
         )])
@@ -124,6 +424,24 @@ impl ConfigHandler for McpServerConfigHandler {
         // type is only used by the selector
         properties.remove("type");
 
+        let transport = match properties.remove("transport") {
+            Some(serde_json::Value::String(s)) => match s.as_str() {
+                "streamable-http" => Transport::StreamableHttp,
+                "stdio" => Transport::Stdio,
+                _ => {
+                    return Err(anyhow::anyhow!(
+                        "Server '{name}': 'transport' must be 'streamable-http' or 'stdio', got '{s}'"
+                    ));
+                }
+            },
+            Some(got) => {
+                return Err(anyhow::anyhow!(
+                    "Server '{name}': 'transport' must be a string, got {got}"
+                ));
+            }
+            None => Transport::StreamableHttp,
+        };
+
         let port = match properties.remove("port") {
             Some(serde_json::Value::Number(n)) => n
                 .as_u64()
@@ -136,6 +454,8 @@ impl ConfigHandler for McpServerConfigHandler {
                     "Server '{name}': 'port' must be a number, got {got}"
                 ));
             }
+            // The port is unused when serving over stdio.
+            None if transport == Transport::Stdio => 0,
             None => {
                 return Err(anyhow::anyhow!(
                     "Server '{name}' missing required 'port' field"
@@ -177,6 +497,23 @@ impl ConfigHandler for McpServerConfigHandler {
             None => None,
         };
 
+        let auth = match properties.remove("auth") {
+            Some(serde_json::Value::Object(map)) => {
+                if transport == Transport::Stdio {
+                    return Err(anyhow::anyhow!(
+                        "Server '{name}': 'auth' is not supported with transport 'stdio'"
+                    ));
+                }
+                Some(parse_auth(name, map)?)
+            }
+            Some(got) => {
+                return Err(anyhow::anyhow!(
+                    "Server '{name}': 'auth' must be a table, got {got}"
+                ));
+            }
+            None => None,
+        };
+
         let component_selector = match properties.remove("component-selector") {
             Some(serde_json::Value::String(s)) => Some(Selector::parse(&s).map_err(|e| {
                 anyhow::anyhow!("Server '{name}': invalid component-selector '{s}': {e}")
@@ -189,6 +526,68 @@ impl ConfigHandler for McpServerConfigHandler {
             None => None,
         };
 
+        let audit = match properties.remove("audit") {
+            Some(serde_json::Value::Object(map)) => Some(parse_audit(name, map)?),
+            Some(got) => {
+                return Err(anyhow::anyhow!(
+                    "Server '{name}': 'audit' must be a table, got {got}"
+                ));
+            }
+            None => None,
+        };
+        if let Some(AuditConfig {
+            output: AuditOutput::Stdout,
+            ..
+        }) = &audit
+            && transport == Transport::Stdio
+        {
+            return Err(anyhow::anyhow!(
+                "Server '{name}': audit 'output' cannot be 'stdout' with transport 'stdio'"
+            ));
+        }
+
+        let tool_naming = match properties.remove("tool-naming") {
+            Some(serde_json::Value::Object(map)) => Some(parse_tool_naming(name, map)?),
+            Some(got) => {
+                return Err(anyhow::anyhow!(
+                    "Server '{name}': 'tool-naming' must be a table, got {got}"
+                ));
+            }
+            None => None,
+        };
+
+        let page_size = match properties.remove("page-size") {
+            Some(serde_json::Value::Number(n)) => Some(
+                n.as_u64()
+                    .filter(|size| *size > 0)
+                    .and_then(|size| usize::try_from(size).ok())
+                    .ok_or_else(|| {
+                        anyhow::anyhow!("Server '{name}': 'page-size' must be a positive integer")
+                    })?,
+            ),
+            Some(got) => {
+                return Err(anyhow::anyhow!(
+                    "Server '{name}': 'page-size' must be a number, got {got}"
+                ));
+            }
+            None => None,
+        };
+
+        let timeout = properties
+            .remove("timeout")
+            .map(|value| {
+                parse_timeout(&value).map_err(|e| anyhow::anyhow!("Server '{name}': 'timeout' {e}"))
+            })
+            .transpose()?;
+
+        let rate_limit = properties
+            .remove("rate-limit")
+            .map(|value| {
+                parse_rate_limit(value)
+                    .map_err(|e| anyhow::anyhow!("Server '{name}': 'rate-limit' {e}"))
+            })
+            .transpose()?;
+
         let otlp_endpoint = match properties.remove("otlp-endpoint") {
             Some(serde_json::Value::String(s)) => Some(s),
             Some(got) => {
@@ -209,11 +608,109 @@ impl ConfigHandler for McpServerConfigHandler {
             None => "grpc".to_string(),
         };
 
-        let tools = parse_tools(name, &mut properties)?;
+        let metrics_path = match properties.remove("metrics-path") {
+            Some(serde_json::Value::String(s)) if is_free_path(&s) => Some(s),
+            Some(got) => {
+                return Err(anyhow::anyhow!(
+                    "Server '{name}': 'metrics-path' must be a path starting with '/' outside \
+                     '/mcp' and '{PROTECTED_RESOURCE_METADATA_PATH}', got {got}"
+                ));
+            }
+            None => None,
+        };
+        let metrics_port = match properties.remove("metrics-port") {
+            Some(serde_json::Value::Number(n)) => Some(
+                n.as_u64()
+                    .and_then(|p| u16::try_from(p).ok())
+                    .filter(|p| *p != port)
+                    .ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "Server '{name}': 'metrics-port' must be a valid port number \
+                             other than 'port'"
+                        )
+                    })?,
+            ),
+            Some(got) => {
+                return Err(anyhow::anyhow!(
+                    "Server '{name}': 'metrics-port' must be a number, got {got}"
+                ));
+            }
+            None => None,
+        };
+        if metrics_path.is_some() && metrics_port.is_none() && transport == Transport::Stdio {
+            return Err(anyhow::anyhow!(
+                "Server '{name}': 'metrics-path' needs 'metrics-port' with transport 'stdio'"
+            ));
+        }
+        let prometheus =
+            (metrics_path.is_some() || metrics_port.is_some()).then(|| PrometheusConfig {
+                path: metrics_path.unwrap_or_else(|| "/metrics".to_string()),
+                port: metrics_port,
+            });
+
+        let defaults = HealthConfig::default();
+        let health = HealthConfig {
+            liveness_path: parse_probe_path(
+                name,
+                "health-path",
+                defaults.liveness_path,
+                &mut properties,
+            )?,
+            readiness_path: parse_probe_path(
+                name,
+                "ready-path",
+                defaults.readiness_path,
+                &mut properties,
+            )?,
+        };
+        // Probes and a metrics endpoint without its own port share the MCP listener.
+        let mut routed: Vec<(&str, &str)> = Vec::new();
+        let paths = [
+            (
+                "metrics-path",
+                prometheus
+                    .as_ref()
+                    .filter(|p| p.port.is_none())
+                    .map(|p| p.path.as_str()),
+            ),
+            ("health-path", health.liveness_path.as_deref()),
+            ("ready-path", health.readiness_path.as_deref()),
+        ];
+        for (key, path) in paths {
+            let Some(path) = path else { continue };
+            if let Some((other, _)) = routed.iter().find(|(_, other)| *other == path) {
+                return Err(anyhow::anyhow!(
+                    "Server '{name}': '{key}' is already used by '{other}'"
+                ));
+            }
+            routed.push((key, path));
+        }
 
-        if component_selector.is_none() && tools.is_empty() {
+        let tools = parse_tools(name, &mut properties)?;
+        // Only OAuth access tokens carry scopes, so other schemes could not enforce them.
+        let oauth = matches!(
+            auth,
+            Some(AuthConfig {
+                scheme: AuthScheme::OAuth(_),
+                ..
+            })
+        );
+        if !oauth && let Some(tool) = tools.iter().find(|tool| !tool.scopes.is_empty()) {
+            return Err(anyhow::anyhow!(
+                "Server '{name}': tool '{}' 'scopes' require auth scheme 'oauth'",
+                tool.name
+            ));
+        }
+        let resources = parse_resources(name, &mut properties)?;
+        let prompts = parse_prompts(name, &mut properties)?;
+
+        if component_selector.is_none()
+            && tools.is_empty()
+            && resources.is_empty()
+            && prompts.is_empty()
+        {
             return Err(anyhow::anyhow!(
-                "Server '{name}' has no tools and no component-selector. \
+                "Server '{name}' has no tools, resources, prompts or component-selector. \
                  At least one must be specified."
             ));
         }
@@ -227,18 +724,255 @@ impl ConfigHandler for McpServerConfigHandler {
 
         self.servers.lock().unwrap().push(McpServerConfig {
             name: name.to_string(),
+            transport,
             host,
             port,
             allowed_origins,
+            auth,
             component_selector,
+            tool_naming,
             tools,
+            resources,
+            prompts,
+            page_size,
+            timeout,
+            rate_limit,
             otlp_endpoint,
             otlp_protocol,
+            prometheus,
+            health,
+            audit,
         });
         Ok(())
     }
 }
 
+/// Errors of invalid server definitions, shared with a `CheckingConfigHandler`.
+pub type ConfigErrors = Arc<Mutex<Vec<anyhow::Error>>>;
+
+/// Handles server definitions like `McpServerConfigHandler`, but records an
+/// invalid definition's error and skips it, so that every one can be reported.
+pub struct CheckingConfigHandler {
+    inner: McpServerConfigHandler,
+    errors: ConfigErrors,
+}
+
+impl CheckingConfigHandler {
+    pub fn new(servers: SharedConfig, errors: ConfigErrors) -> Self {
+        Self {
+            inner: McpServerConfigHandler::new(servers),
+            errors,
+        }
+    }
+}
+
+impl ConfigHandler for CheckingConfigHandler {
+    fn claimed_categories(&self) -> Vec<CategoryClaim> {
+        self.inner.claimed_categories()
+    }
+
+    fn claimed_properties(&self) -> HashMap<&str, &[&str]> {
+        self.inner.claimed_properties()
+    }
+
+    fn handle_category(
+        &mut self,
+        category: &str,
+        name: &str,
+        properties: PropertyMap,
+    ) -> Result<()> {
+        if let Err(e) = self.inner.handle_category(category, name, properties) {
+            self.errors.lock().unwrap().push(e);
+        }
+        Ok(())
+    }
+}
+
+fn parse_auth(
+    server_name: &str,
+    mut auth_props: serde_json::Map<String, serde_json::Value>,
+) -> Result<AuthConfig> {
+    let scheme = match auth_props.remove("scheme") {
+        Some(serde_json::Value::String(s)) => s,
+        Some(got) => {
+            return Err(anyhow::anyhow!(
+                "Server '{server_name}': auth 'scheme' must be a string, got {got}"
+            ));
+        }
+        None => "bearer".to_string(),
+    };
+
+    let header = match auth_props.remove("header") {
+        Some(serde_json::Value::String(s)) => Some(s),
+        Some(got) => {
+            return Err(anyhow::anyhow!(
+                "Server '{server_name}': auth 'header' must be a string, got {got}"
+            ));
+        }
+        None => None,
+    };
+
+    let scheme = match (scheme.as_str(), header) {
+        ("bearer", None) => AuthScheme::Bearer,
+        ("api-key", header) => AuthScheme::ApiKey {
+            header: header.unwrap_or_else(|| "X-API-Key".to_string()),
+        },
+        ("oauth", None) => AuthScheme::OAuth(parse_oauth(server_name, &mut auth_props)?),
+        ("bearer", Some(_)) | ("oauth", Some(_)) => {
+            return Err(anyhow::anyhow!(
+                "Server '{server_name}': auth 'header' only applies to scheme 'api-key'"
+            ));
+        }
+        (other, _) => {
+            return Err(anyhow::anyhow!(
+                "Server '{server_name}': auth 'scheme' must be 'bearer', 'api-key' or 'oauth', got '{other}'"
+            ));
+        }
+    };
+
+    let credentials = match auth_props.remove("credentials") {
+        Some(serde_json::Value::Array(items)) => items
+            .into_iter()
+            .map(|item| parse_credential(server_name, item))
+            .collect::<Result<Vec<_>>>()?,
+        Some(got) => {
+            return Err(anyhow::anyhow!(
+                "Server '{server_name}': auth 'credentials' must be an array, got {got}"
+            ));
+        }
+        None => Vec::new(),
+    };
+    match (&scheme, credentials.is_empty()) {
+        (AuthScheme::OAuth(_), false) => {
+            return Err(anyhow::anyhow!(
+                "Server '{server_name}': auth 'credentials' does not apply to scheme 'oauth'"
+            ));
+        }
+        (AuthScheme::Bearer | AuthScheme::ApiKey { .. }, true) => {
+            return Err(anyhow::anyhow!(
+                "Server '{server_name}': auth requires at least one entry in 'credentials'"
+            ));
+        }
+        _ => {}
+    }
+
+    if !auth_props.is_empty() {
+        let unknown: Vec<_> = auth_props.keys().collect();
+        return Err(anyhow::anyhow!(
+            "Server '{server_name}': auth has unknown properties: {unknown:?}"
+        ));
+    }
+
+    Ok(AuthConfig {
+        scheme,
+        credentials,
+    })
+}
+
+fn parse_oauth(
+    server_name: &str,
+    auth_props: &mut serde_json::Map<String, serde_json::Value>,
+) -> Result<OAuthConfig> {
+    let mut take_string = |key: &str| -> Result<Option<String>> {
+        match auth_props.remove(key) {
+            Some(serde_json::Value::String(s)) => Ok(Some(s)),
+            Some(got) => Err(anyhow::anyhow!(
+                "Server '{server_name}': auth '{key}' must be a string, got {got}"
+            )),
+            None => Ok(None),
+        }
+    };
+
+    let issuer = take_string("issuer")?;
+    let audience = take_string("audience")?;
+    let jwks = take_string("jwks")?;
+    let resource = take_string("resource")?;
+
+    let (Some(issuer), Some(audience), Some(jwks)) = (issuer, audience, jwks) else {
+        return Err(anyhow::anyhow!(
+            "Server '{server_name}': auth scheme 'oauth' requires 'issuer', 'audience' and 'jwks'"
+        ));
+    };
+
+    let jwks = if jwks.starts_with("https://") || jwks.starts_with("http://") {
+        JwksSource::Url(jwks)
+    } else {
+        JwksSource::File(PathBuf::from(jwks.strip_prefix("file://").unwrap_or(&jwks)))
+    };
+
+    let authorization_servers = match auth_props.remove("authorization-servers") {
+        Some(serde_json::Value::Array(items)) => items
+            .into_iter()
+            .map(|item| match item {
+                serde_json::Value::String(s) => Ok(s),
+                got => Err(anyhow::anyhow!(
+                    "Server '{server_name}': auth 'authorization-servers' items must be strings, got {got}"
+                )),
+            })
+            .collect::<Result<Vec<_>>>()?,
+        Some(got) => {
+            return Err(anyhow::anyhow!(
+                "Server '{server_name}': auth 'authorization-servers' must be an array, got {got}"
+            ));
+        }
+        None => vec![issuer.clone()],
+    };
+
+    Ok(OAuthConfig {
+        resource: resource.unwrap_or_else(|| audience.clone()),
+        issuer,
+        audience,
+        jwks,
+        authorization_servers,
+    })
+}
+
+fn parse_credential(server_name: &str, value: serde_json::Value) -> Result<CredentialConfig> {
+    let mut props = match value {
+        serde_json::Value::Object(map) => map,
+        got => {
+            return Err(anyhow::anyhow!(
+                "Server '{server_name}': auth credentials must be tables, got {got}"
+            ));
+        }
+    };
+
+    let mut take_string = |key: &str| -> Result<Option<String>> {
+        match props.remove(key) {
+            Some(serde_json::Value::String(s)) => Ok(Some(s)),
+            Some(got) => Err(anyhow::anyhow!(
+                "Server '{server_name}': auth credential '{key}' must be a string, got {got}"
+            )),
+            None => Ok(None),
+        }
+    };
+
+    let principal = take_string("principal")?.ok_or_else(|| {
+        anyhow::anyhow!("Server '{server_name}': auth credential missing required 'principal'")
+    })?;
+    let env = take_string("env")?;
+    let file = take_string("file")?;
+
+    let secret = match (env, file) {
+        (Some(env), None) => SecretSource::Env(env),
+        (None, Some(file)) => SecretSource::File(PathBuf::from(file)),
+        _ => {
+            return Err(anyhow::anyhow!(
+                "Server '{server_name}': auth credential '{principal}' must have exactly one of 'env' or 'file'"
+            ));
+        }
+    };
+
+    if !props.is_empty() {
+        let unknown: Vec<_> = props.keys().collect();
+        return Err(anyhow::anyhow!(
+            "Server '{server_name}': auth credential '{principal}' has unknown properties: {unknown:?}"
+        ));
+    }
+
+    Ok(CredentialConfig { principal, secret })
+}
+
 fn parse_tools(server_name: &str, properties: &mut PropertyMap) -> Result<Vec<ToolConfig>> {
     let tool_table = match properties.remove("tool") {
         Some(serde_json::Value::Object(map)) => map,
@@ -293,6 +1027,8 @@ fn parse_tools(server_name: &str, properties: &mut PropertyMap) -> Result<Vec<To
 
         let input_schema = tool_props.remove("input-schema");
         let output_schema = tool_props.remove("output-schema");
+        let defaults = take_argument_map(server_name, &tool_name, &mut tool_props, "defaults")?;
+        let fixed = take_argument_map(server_name, &tool_name, &mut tool_props, "fixed")?;
 
         let target = match (component, function, channel) {
             (Some(component), Some(function), None) => {
@@ -302,18 +1038,27 @@ fn parse_tools(server_name: &str, properties: &mut PropertyMap) -> Result<Vec<To
                          but component-backed tools derive their schema directly from WIT"
                     ));
                 }
-                if output_schema.is_some() {
+                if let Some(name) = defaults.keys().find(|name| fixed.contains_key(*name)) {
                     return Err(anyhow::anyhow!(
-                        "Server '{server_name}': tool '{tool_name}' has 'output-schema' \
-                         but component-backed tools derive their schema directly from WIT"
+                        "Server '{server_name}': tool '{tool_name}' parameter '{name}' \
+                         cannot be in both 'defaults' and 'fixed'"
                     ));
                 }
                 ToolTarget::Component {
                     component,
                     function,
+                    output_schema,
+                    defaults,
+                    fixed,
                 }
             }
             (None, None, Some(channel)) => {
+                if !defaults.is_empty() || !fixed.is_empty() {
+                    return Err(anyhow::anyhow!(
+                        "Server '{server_name}': channel-backed tool '{tool_name}' \
+                         does not support 'defaults' or 'fixed'"
+                    ));
+                }
                 let input_schema = input_schema.ok_or_else(|| {
                     anyhow::anyhow!(
                         "Server '{server_name}': channel-backed tool '{tool_name}' requires 'input-schema'"
@@ -359,23 +1104,509 @@ fn parse_tools(server_name: &str, properties: &mut PropertyMap) -> Result<Vec<To
             None => None,
         };
 
-        if !tool_props.is_empty() {
-            let unknown: Vec<_> = tool_props.keys().collect();
-            return Err(anyhow::anyhow!(
-                "Server '{server_name}': tool '{tool_name}' has unknown properties: {unknown:?}"
-            ));
-        }
-
-        tools.push(ToolConfig {
-            name: tool_name,
+        let scopes = match tool_props.remove("scopes") {
+            Some(serde_json::Value::Array(items)) => items
+                .into_iter()
+                .map(|item| match item {
+                    serde_json::Value::String(s) => Ok(s),
+                    got => Err(anyhow::anyhow!(
+                        "Server '{server_name}': tool '{tool_name}' 'scopes' items must be strings, got {got}"
+                    )),
+                })
+                .collect::<Result<Vec<_>>>()?,
+            Some(got) => {
+                return Err(anyhow::anyhow!(
+                    "Server '{server_name}': tool '{tool_name}' 'scopes' must be an array, got {got}"
+                ));
+            }
+            None => Vec::new(),
+        };
+
+        let timeout = tool_props
+            .remove("timeout")
+            .map(|value| {
+                parse_timeout(&value).map_err(|e| {
+                    anyhow::anyhow!("Server '{server_name}': tool '{tool_name}' 'timeout' {e}")
+                })
+            })
+            .transpose()?;
+
+        let rate_limit = tool_props
+            .remove("rate-limit")
+            .map(|value| {
+                parse_rate_limit(value).map_err(|e| {
+                    anyhow::anyhow!("Server '{server_name}': tool '{tool_name}' 'rate-limit' {e}")
+                })
+            })
+            .transpose()?;
+
+        let annotations = match tool_props.remove("annotations") {
+            Some(serde_json::Value::Object(map)) => {
+                Some(parse_annotations(server_name, &tool_name, map)?)
+            }
+            Some(got) => {
+                return Err(anyhow::anyhow!(
+                    "Server '{server_name}': tool '{tool_name}' 'annotations' must be a table, got {got}"
+                ));
+            }
+            None => None,
+        };
+
+        if !tool_props.is_empty() {
+            let unknown: Vec<_> = tool_props.keys().collect();
+            return Err(anyhow::anyhow!(
+                "Server '{server_name}': tool '{tool_name}' has unknown properties: {unknown:?}"
+            ));
+        }
+
+        tools.push(ToolConfig {
+            name: tool_name,
             target,
             description,
+            scopes,
+            timeout,
+            rate_limit,
+            annotations,
         });
     }
 
     Ok(tools)
 }
 
+// Remove an optional table of argument values keyed by parameter name.
+fn take_argument_map(
+    server_name: &str,
+    tool_name: &str,
+    tool_props: &mut serde_json::Map<String, serde_json::Value>,
+    key: &str,
+) -> Result<serde_json::Map<String, serde_json::Value>> {
+    match tool_props.remove(key) {
+        Some(serde_json::Value::Object(map)) => Ok(map),
+        Some(got) => Err(anyhow::anyhow!(
+            "Server '{server_name}': tool '{tool_name}' '{key}' must be a table, got {got}"
+        )),
+        None => Ok(serde_json::Map::new()),
+    }
+}
+
+// Parse a probe path: a path starting with '/', or `false` to disable the probe.
+fn parse_probe_path(
+    name: &str,
+    key: &str,
+    default: Option<String>,
+    properties: &mut PropertyMap,
+) -> Result<Option<String>> {
+    match properties.remove(key) {
+        Some(serde_json::Value::String(s)) if is_free_path(&s) => Ok(Some(s)),
+        Some(serde_json::Value::Bool(false)) => Ok(None),
+        Some(got) => Err(anyhow::anyhow!(
+            "Server '{name}': '{key}' must be a path starting with '/' outside '/mcp' and \
+             '{PROTECTED_RESOURCE_METADATA_PATH}', or false, got {got}"
+        )),
+        None => Ok(default),
+    }
+}
+
+// Whether a path starts with '/' and is not routed to the MCP endpoint or the
+// OAuth metadata document, including their sub-paths.
+fn is_free_path(path: &str) -> bool {
+    path.starts_with('/')
+        && ["/mcp", PROTECTED_RESOURCE_METADATA_PATH]
+            .iter()
+            .all(|reserved| {
+                path.strip_prefix(reserved)
+                    .is_none_or(|rest| !rest.is_empty() && !rest.starts_with('/'))
+            })
+}
+
+fn parse_audit(
+    server_name: &str,
+    mut props: serde_json::Map<String, serde_json::Value>,
+) -> Result<AuditConfig> {
+    let output = match props.remove("output") {
+        Some(serde_json::Value::String(s)) if s == "stdout" => AuditOutput::Stdout,
+        Some(serde_json::Value::String(s)) if !s.is_empty() => AuditOutput::File(PathBuf::from(s)),
+        Some(got) => {
+            return Err(anyhow::anyhow!(
+                "Server '{server_name}': audit 'output' must be 'stdout' or a file path, got {got}"
+            ));
+        }
+        None => {
+            return Err(anyhow::anyhow!(
+                "Server '{server_name}': audit requires 'output'"
+            ));
+        }
+    };
+    let redact = match props.remove("redact") {
+        Some(serde_json::Value::Array(items)) => items
+            .into_iter()
+            .map(|item| match item {
+                serde_json::Value::String(s) if s.starts_with('/') => Ok(s),
+                got => Err(anyhow::anyhow!(
+                    "Server '{server_name}': audit 'redact' items must be JSON pointers \
+                     starting with '/', got {got}"
+                )),
+            })
+            .collect::<Result<Vec<_>>>()?,
+        Some(got) => {
+            return Err(anyhow::anyhow!(
+                "Server '{server_name}': audit 'redact' must be an array, got {got}"
+            ));
+        }
+        None => Vec::new(),
+    };
+    if !props.is_empty() {
+        let unknown: Vec<_> = props.keys().collect();
+        return Err(anyhow::anyhow!(
+            "Server '{server_name}': audit has unknown properties: {unknown:?}"
+        ));
+    }
+    Ok(AuditConfig { output, redact })
+}
+
+fn parse_tool_naming(
+    server_name: &str,
+    mut props: serde_json::Map<String, serde_json::Value>,
+) -> Result<ToolNamingConfig> {
+    let is_name_chars = |s: &str| {
+        s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    };
+    let prefix = match props.remove("prefix") {
+        Some(serde_json::Value::String(s)) if !s.is_empty() && is_name_chars(&s) => Some(s),
+        Some(got) => {
+            return Err(anyhow::anyhow!(
+                "Server '{server_name}': tool-naming 'prefix' must be a non-empty string of \
+                 letters, digits, '_' or '-', got {got}"
+            ));
+        }
+        None => None,
+    };
+    let separator = match props.remove("separator") {
+        Some(serde_json::Value::String(s)) if is_name_chars(&s) => s,
+        Some(got) => {
+            return Err(anyhow::anyhow!(
+                "Server '{server_name}': tool-naming 'separator' may only contain letters, \
+                 digits, '_' or '-', got {got}"
+            ));
+        }
+        None => "_".to_string(),
+    };
+    let case = match props.remove("case") {
+        Some(serde_json::Value::String(s)) => match s.as_str() {
+            "preserve" => NameCase::Preserve,
+            "snake" => NameCase::Snake,
+            "kebab" => NameCase::Kebab,
+            "camel" => NameCase::Camel,
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "Server '{server_name}': tool-naming 'case' must be 'preserve', 'snake', \
+                     'kebab' or 'camel', got '{s}'"
+                ));
+            }
+        },
+        Some(got) => {
+            return Err(anyhow::anyhow!(
+                "Server '{server_name}': tool-naming 'case' must be a string, got {got}"
+            ));
+        }
+        None => NameCase::Preserve,
+    };
+    let max_length = match props.remove("max-length") {
+        Some(serde_json::Value::Number(n)) => n
+            .as_u64()
+            .and_then(|len| usize::try_from(len).ok())
+            .filter(|len| (1..=MAX_TOOL_NAME_LENGTH).contains(len))
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Server '{server_name}': tool-naming 'max-length' must be between 1 and \
+                     {MAX_TOOL_NAME_LENGTH}"
+                )
+            })?,
+        Some(got) => {
+            return Err(anyhow::anyhow!(
+                "Server '{server_name}': tool-naming 'max-length' must be a number, got {got}"
+            ));
+        }
+        None => MAX_TOOL_NAME_LENGTH,
+    };
+    if !props.is_empty() {
+        let unknown: Vec<_> = props.keys().collect();
+        return Err(anyhow::anyhow!(
+            "Server '{server_name}': tool-naming has unknown properties: {unknown:?}"
+        ));
+    }
+    Ok(ToolNamingConfig {
+        prefix,
+        separator,
+        case,
+        max_length,
+    })
+}
+
+fn parse_annotations(
+    server_name: &str,
+    tool_name: &str,
+    mut props: serde_json::Map<String, serde_json::Value>,
+) -> Result<ToolAnnotationsConfig> {
+    let mut annotations = ToolAnnotationsConfig::default();
+    annotations.title = match props.remove("title") {
+        Some(serde_json::Value::String(s)) => Some(s),
+        Some(got) => {
+            return Err(anyhow::anyhow!(
+                "Server '{server_name}': tool '{tool_name}' annotation 'title' must be a string, got {got}"
+            ));
+        }
+        None => None,
+    };
+    for key in ANNOTATION_HINTS {
+        *annotations.hint_mut(key) = match props.remove(key) {
+            Some(serde_json::Value::Bool(b)) => Some(b),
+            Some(got) => {
+                return Err(anyhow::anyhow!(
+                    "Server '{server_name}': tool '{tool_name}' annotation '{key}' must be a boolean, got {got}"
+                ));
+            }
+            None => None,
+        };
+    }
+    if !props.is_empty() {
+        let unknown: Vec<_> = props.keys().collect();
+        return Err(anyhow::anyhow!(
+            "Server '{server_name}': tool '{tool_name}' has unknown annotations: {unknown:?}"
+        ));
+    }
+    annotations
+        .validate()
+        .map_err(|e| anyhow::anyhow!("Server '{server_name}': tool '{tool_name}' {e}"))?;
+    Ok(annotations)
+}
+
+// Parse a rate limit table: `requests` (required), `per` (a duration, default
+// one second) and `burst` (default `requests`).
+fn parse_rate_limit(value: serde_json::Value) -> std::result::Result<RateLimitConfig, String> {
+    let serde_json::Value::Object(mut props) = value else {
+        return Err(format!("must be a table, got {value}"));
+    };
+    let positive = |key: &str, value: serde_json::Value| {
+        value
+            .as_u64()
+            .and_then(|n| u32::try_from(n).ok())
+            .filter(|n| *n > 0)
+            .ok_or_else(|| format!("'{key}' must be a positive integer, got {value}"))
+    };
+    let requests = match props.remove("requests") {
+        Some(value) => positive("requests", value)?,
+        None => return Err("requires 'requests'".to_string()),
+    };
+    let per = match props.remove("per") {
+        Some(value) => parse_timeout(&value).map_err(|e| format!("'per' {e}"))?,
+        None => Duration::from_secs(1),
+    };
+    let burst = match props.remove("burst") {
+        Some(value) => positive("burst", value)?,
+        None => requests,
+    };
+    if !props.is_empty() {
+        let unknown: Vec<_> = props.keys().collect();
+        return Err(format!("has unknown properties: {unknown:?}"));
+    }
+    Ok(RateLimitConfig {
+        requests,
+        per,
+        burst,
+    })
+}
+
+// Parse a timeout given as a number of seconds or a string with an `ms`, `s` or `m` suffix.
+fn parse_timeout(value: &serde_json::Value) -> std::result::Result<Duration, String> {
+    let duration = match value {
+        serde_json::Value::Number(n) => n
+            .as_f64()
+            .and_then(|secs| Duration::try_from_secs_f64(secs).ok()),
+        serde_json::Value::String(s) => {
+            let s = s.trim();
+            let (number, unit_secs) = if let Some(ms) = s.strip_suffix("ms") {
+                (ms, 0.001)
+            } else if let Some(secs) = s.strip_suffix('s') {
+                (secs, 1.0)
+            } else if let Some(mins) = s.strip_suffix('m') {
+                (mins, 60.0)
+            } else {
+                (s, 1.0)
+            };
+            number
+                .trim()
+                .parse::<f64>()
+                .ok()
+                .and_then(|n| Duration::try_from_secs_f64(n * unit_secs).ok())
+        }
+        got => {
+            return Err(format!(
+                "must be a number of seconds or a duration string, got {got}"
+            ));
+        }
+    };
+    duration.filter(|d| !d.is_zero()).ok_or_else(|| {
+        format!("must be a positive duration such as 30, \"500ms\", \"30s\" or \"2m\", got {value}")
+    })
+}
+
+fn parse_resources(server_name: &str, properties: &mut PropertyMap) -> Result<Vec<ResourceConfig>> {
+    let resource_table = match properties.remove("resource") {
+        Some(serde_json::Value::Object(map)) => map,
+        Some(got) => {
+            return Err(anyhow::anyhow!(
+                "Server '{server_name}': 'resource' must be a table, got {got}"
+            ));
+        }
+        None => return Ok(Vec::new()),
+    };
+
+    let mut resources = Vec::new();
+    for (resource_name, resource_value) in resource_table {
+        let mut resource_props = match resource_value {
+            serde_json::Value::Object(map) => map,
+            got => {
+                return Err(anyhow::anyhow!(
+                    "Server '{server_name}': resource '{resource_name}' must be a table, got {got}"
+                ));
+            }
+        };
+
+        let mut take_string = |key: &str| -> Result<Option<String>> {
+            match resource_props.remove(key) {
+                Some(serde_json::Value::String(s)) => Ok(Some(s)),
+                Some(got) => Err(anyhow::anyhow!(
+                    "Server '{server_name}': resource '{resource_name}' '{key}' must be a string, got {got}"
+                )),
+                None => Ok(None),
+            }
+        };
+
+        let uri = take_string("uri")?;
+        let component = take_string("component")?;
+        let function = take_string("function")?;
+        let description = take_string("description")?;
+        let mime_type = take_string("mime-type")?;
+
+        let Some(uri) = uri else {
+            return Err(anyhow::anyhow!(
+                "Server '{server_name}': resource '{resource_name}' missing required 'uri'"
+            ));
+        };
+        let (component, function) = match (component, function) {
+            (Some(component), Some(function)) => (component, function),
+            (Some(_), None) => {
+                return Err(anyhow::anyhow!(
+                    "Server '{server_name}': resource '{resource_name}' has 'component' but missing 'function'"
+                ));
+            }
+            (None, Some(_)) => {
+                return Err(anyhow::anyhow!(
+                    "Server '{server_name}': resource '{resource_name}' has 'function' but missing 'component'"
+                ));
+            }
+            (None, None) => {
+                return Err(anyhow::anyhow!(
+                    "Server '{server_name}': resource '{resource_name}' must have 'component' and 'function'"
+                ));
+            }
+        };
+
+        if !resource_props.is_empty() {
+            let unknown: Vec<_> = resource_props.keys().collect();
+            return Err(anyhow::anyhow!(
+                "Server '{server_name}': resource '{resource_name}' has unknown properties: {unknown:?}"
+            ));
+        }
+
+        resources.push(ResourceConfig {
+            name: resource_name,
+            uri,
+            component,
+            function,
+            description,
+            mime_type,
+        });
+    }
+
+    Ok(resources)
+}
+
+fn parse_prompts(server_name: &str, properties: &mut PropertyMap) -> Result<Vec<PromptConfig>> {
+    let prompt_table = match properties.remove("prompt") {
+        Some(serde_json::Value::Object(map)) => map,
+        Some(got) => {
+            return Err(anyhow::anyhow!(
+                "Server '{server_name}': 'prompt' must be a table, got {got}"
+            ));
+        }
+        None => return Ok(Vec::new()),
+    };
+
+    let mut prompts = Vec::new();
+    for (prompt_name, prompt_value) in prompt_table {
+        let mut prompt_props = match prompt_value {
+            serde_json::Value::Object(map) => map,
+            got => {
+                return Err(anyhow::anyhow!(
+                    "Server '{server_name}': prompt '{prompt_name}' must be a table, got {got}"
+                ));
+            }
+        };
+
+        let mut take_string = |key: &str| -> Result<Option<String>> {
+            match prompt_props.remove(key) {
+                Some(serde_json::Value::String(s)) => Ok(Some(s)),
+                Some(got) => Err(anyhow::anyhow!(
+                    "Server '{server_name}': prompt '{prompt_name}' '{key}' must be a string, got {got}"
+                )),
+                None => Ok(None),
+            }
+        };
+
+        let component = take_string("component")?;
+        let function = take_string("function")?;
+        let description = take_string("description")?;
+
+        let (component, function) = match (component, function) {
+            (Some(component), Some(function)) => (component, function),
+            (Some(_), None) => {
+                return Err(anyhow::anyhow!(
+                    "Server '{server_name}': prompt '{prompt_name}' has 'component' but missing 'function'"
+                ));
+            }
+            (None, Some(_)) => {
+                return Err(anyhow::anyhow!(
+                    "Server '{server_name}': prompt '{prompt_name}' has 'function' but missing 'component'"
+                ));
+            }
+            (None, None) => {
+                return Err(anyhow::anyhow!(
+                    "Server '{server_name}': prompt '{prompt_name}' must have 'component' and 'function'"
+                ));
+            }
+        };
+
+        if !prompt_props.is_empty() {
+            let unknown: Vec<_> = prompt_props.keys().collect();
+            return Err(anyhow::anyhow!(
+                "Server '{server_name}': prompt '{prompt_name}' has unknown properties: {unknown:?}"
+            ));
+        }
+
+        prompts.push(PromptConfig {
+            name: prompt_name,
+            component,
+            function,
+            description,
+        });
+    }
+
+    Ok(prompts)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -414,13 +1645,14 @@ mod tests {
         let servers = config.lock().unwrap();
         assert_eq!(servers.len(), 1);
         assert_eq!(servers[0].name, "mcp");
+        assert_eq!(servers[0].transport, Transport::StreamableHttp);
         assert_eq!(servers[0].host, "127.0.0.1");
         assert_eq!(servers[0].port, 3001);
         assert!(servers[0].allowed_origins.is_none());
         assert_eq!(servers[0].tools.len(), 1);
         assert_eq!(servers[0].tools[0].name, "add-two");
         assert!(
-            matches!(&servers[0].tools[0].target, ToolTarget::Component { component, function }
+            matches!(&servers[0].tools[0].target, ToolTarget::Component { component, function, output_schema: None, .. }
                 if component == "math" && function == "add-two")
         );
         assert!(servers[0].tools[0].description.is_none());
@@ -660,7 +1892,7 @@ mod tests {
             result
                 .unwrap_err()
                 .to_string()
-                .contains("no tools and no component-selector")
+                .contains("no tools, resources, prompts or component-selector")
         );
     }
 
@@ -776,4 +2008,1000 @@ mod tests {
         assert!(servers[0].component_selector.is_some());
         assert_eq!(servers[0].tools.len(), 1);
     }
+
+    #[test]
+    fn parse_resource() {
+        let (mut handler, config) = make_handler();
+        let properties = props(vec![
+            ("type", serde_json::json!("mcp")),
+            ("port", serde_json::json!(3001)),
+            (
+                "resource",
+                serde_json::json!({
+                    "user": {
+                        "uri": "users://{id}/profile",
+                        "component": "users",
+                        "function": "get-profile",
+                        "description": "A user profile",
+                        "mime-type": "application/json"
+                    }
+                }),
+            ),
+        ]);
+
+        handler
+            .handle_category("server", "mcp", properties)
+            .unwrap();
+
+        let servers = config.lock().unwrap();
+        assert!(servers[0].tools.is_empty());
+        assert_eq!(servers[0].resources.len(), 1);
+        let resource = &servers[0].resources[0];
+        assert_eq!(resource.name, "user");
+        assert_eq!(resource.uri, "users://{id}/profile");
+        assert_eq!(resource.component, "users");
+        assert_eq!(resource.function, "get-profile");
+        assert_eq!(resource.description.as_deref(), Some("A user profile"));
+        assert_eq!(resource.mime_type.as_deref(), Some("application/json"));
+    }
+
+    #[test]
+    fn resource_requires_uri() {
+        let (mut handler, _) = make_handler();
+        let properties = props(vec![
+            ("type", serde_json::json!("mcp")),
+            ("port", serde_json::json!(3001)),
+            (
+                "resource",
+                serde_json::json!({
+                    "bad": {
+                        "component": "docs",
+                        "function": "read"
+                    }
+                }),
+            ),
+        ]);
+
+        let result = handler.handle_category("server", "mcp", properties);
+        assert!(result.is_err());
+        let err = result.unwrap_err().to_string();
+        assert!(
+            err.contains("missing required 'uri'"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn resource_requires_function() {
+        let (mut handler, _) = make_handler();
+        let properties = props(vec![
+            ("type", serde_json::json!("mcp")),
+            ("port", serde_json::json!(3001)),
+            (
+                "resource",
+                serde_json::json!({
+                    "bad": {
+                        "uri": "docs://readme",
+                        "component": "docs"
+                    }
+                }),
+            ),
+        ]);
+
+        let result = handler.handle_category("server", "mcp", properties);
+        assert!(result.is_err());
+        let err = result.unwrap_err().to_string();
+        assert!(
+            err.contains("'component' but missing 'function'"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn parse_prompt() {
+        let (mut handler, config) = make_handler();
+        let properties = props(vec![
+            ("type", serde_json::json!("mcp")),
+            ("port", serde_json::json!(3001)),
+            (
+                "prompt",
+                serde_json::json!({
+                    "code-review": {
+                        "component": "prompts",
+                        "function": "code-review",
+                        "description": "Review a diff"
+                    }
+                }),
+            ),
+        ]);
+
+        handler
+            .handle_category("server", "mcp", properties)
+            .unwrap();
+
+        let servers = config.lock().unwrap();
+        assert_eq!(servers[0].prompts.len(), 1);
+        let prompt = &servers[0].prompts[0];
+        assert_eq!(prompt.name, "code-review");
+        assert_eq!(prompt.component, "prompts");
+        assert_eq!(prompt.function, "code-review");
+        assert_eq!(prompt.description.as_deref(), Some("Review a diff"));
+    }
+
+    #[test]
+    fn unknown_prompt_property() {
+        let (mut handler, _) = make_handler();
+        let properties = props(vec![
+            ("type", serde_json::json!("mcp")),
+            ("port", serde_json::json!(3001)),
+            (
+                "prompt",
+                serde_json::json!({
+                    "bad": {
+                        "component": "prompts",
+                        "function": "review",
+                        "input-schema": {}
+                    }
+                }),
+            ),
+        ]);
+
+        let result = handler.handle_category("server", "mcp", properties);
+        assert!(result.is_err());
+        assert!(
+            result
+                .unwrap_err()
+                .to_string()
+                .contains("unknown properties")
+        );
+    }
+
+    #[test]
+    fn stdio_transport_without_port() {
+        let (mut handler, config) = make_handler();
+        let properties = props(vec![
+            ("type", serde_json::json!("mcp")),
+            ("transport", serde_json::json!("stdio")),
+            ("component-selector", serde_json::json!("!dependents")),
+        ]);
+
+        handler
+            .handle_category("server", "mcp", properties)
+            .unwrap();
+
+        let servers = config.lock().unwrap();
+        assert_eq!(servers[0].transport, Transport::Stdio);
+    }
+
+    #[test]
+    fn unknown_transport() {
+        let (mut handler, _) = make_handler();
+        let properties = props(vec![
+            ("type", serde_json::json!("mcp")),
+            ("port", serde_json::json!(3001)),
+            ("transport", serde_json::json!("websocket")),
+            ("component-selector", serde_json::json!("!dependents")),
+        ]);
+
+        let result = handler.handle_category("server", "mcp", properties);
+        assert!(result.is_err());
+        let err = result.unwrap_err().to_string();
+        assert!(
+            err.contains("must be 'streamable-http' or 'stdio'"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn parse_page_size() {
+        let (mut handler, config) = make_handler();
+        let properties = props(vec![
+            ("type", serde_json::json!("mcp")),
+            ("port", serde_json::json!(3001)),
+            ("page-size", serde_json::json!(50)),
+            ("component-selector", serde_json::json!("!dependents")),
+        ]);
+
+        handler
+            .handle_category("server", "mcp", properties)
+            .unwrap();
+
+        let servers = config.lock().unwrap();
+        assert_eq!(servers[0].page_size, Some(50));
+    }
+
+    #[test]
+    fn zero_page_size() {
+        let (mut handler, _) = make_handler();
+        let properties = props(vec![
+            ("type", serde_json::json!("mcp")),
+            ("port", serde_json::json!(3001)),
+            ("page-size", serde_json::json!(0)),
+            ("component-selector", serde_json::json!("!dependents")),
+        ]);
+
+        let result = handler.handle_category("server", "mcp", properties);
+        assert!(result.is_err());
+        let err = result.unwrap_err().to_string();
+        assert!(
+            err.contains("'page-size' must be a positive integer"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn parse_tool_naming() {
+        let (mut handler, config) = make_handler();
+        let properties = props(vec![
+            ("type", serde_json::json!("mcp")),
+            ("port", serde_json::json!(3001)),
+            ("component-selector", serde_json::json!("!dependents")),
+            (
+                "tool-naming",
+                serde_json::json!({
+                    "prefix": "acme",
+                    "separator": "-",
+                    "case": "kebab",
+                    "max-length": 48
+                }),
+            ),
+        ]);
+
+        handler
+            .handle_category("server", "mcp", properties)
+            .unwrap();
+
+        let servers = config.lock().unwrap();
+        assert_eq!(
+            servers[0].tool_naming,
+            Some(ToolNamingConfig {
+                prefix: Some("acme".to_string()),
+                separator: "-".to_string(),
+                case: NameCase::Kebab,
+                max_length: 48,
+            })
+        );
+    }
+
+    #[test]
+    fn parse_metrics_endpoint() {
+        let parse = |extra: Vec<(&str, serde_json::Value)>| {
+            let (mut handler, config) = make_handler();
+            let mut pairs = vec![
+                ("type", serde_json::json!("mcp")),
+                ("port", serde_json::json!(3001)),
+                ("component-selector", serde_json::json!("!dependents")),
+            ];
+            pairs.extend(extra);
+            handler
+                .handle_category("server", "mcp", props(pairs))
+                .map(|_| config.lock().unwrap()[0].prometheus.clone())
+        };
+
+        assert_eq!(parse(vec![]).unwrap(), None);
+        assert_eq!(
+            parse(vec![("metrics-path", serde_json::json!("/metrics"))]).unwrap(),
+            Some(PrometheusConfig {
+                path: "/metrics".to_string(),
+                port: None,
+            })
+        );
+        assert_eq!(
+            parse(vec![("metrics-port", serde_json::json!(9464))]).unwrap(),
+            Some(PrometheusConfig {
+                path: "/metrics".to_string(),
+                port: Some(9464),
+            })
+        );
+        for (key, value) in [
+            ("metrics-path", serde_json::json!("metrics")),
+            ("metrics-path", serde_json::json!("/mcp")),
+            ("metrics-path", serde_json::json!("/mcp/metrics")),
+            (
+                "metrics-path",
+                serde_json::json!("/.well-known/oauth-protected-resource"),
+            ),
+            ("metrics-port", serde_json::json!(3001)),
+        ] {
+            let err = parse(vec![(key, value)]).unwrap_err().to_string();
+            assert!(err.contains(key), "unexpected error: {err}");
+        }
+        let err = parse(vec![
+            ("transport", serde_json::json!("stdio")),
+            ("metrics-path", serde_json::json!("/metrics")),
+        ])
+        .unwrap_err()
+        .to_string();
+        assert!(
+            err.contains("needs 'metrics-port'"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn parse_health_paths() {
+        let parse = |extra: Vec<(&str, serde_json::Value)>| {
+            let (mut handler, config) = make_handler();
+            let mut pairs = vec![
+                ("type", serde_json::json!("mcp")),
+                ("port", serde_json::json!(3001)),
+                ("component-selector", serde_json::json!("!dependents")),
+            ];
+            pairs.extend(extra);
+            handler
+                .handle_category("server", "mcp", props(pairs))
+                .map(|_| config.lock().unwrap()[0].health.clone())
+        };
+
+        assert_eq!(parse(vec![]).unwrap(), HealthConfig::default());
+        assert_eq!(
+            parse(vec![
+                ("health-path", serde_json::json!("/live")),
+                ("ready-path", serde_json::json!(false)),
+            ])
+            .unwrap(),
+            HealthConfig {
+                liveness_path: Some("/live".to_string()),
+                readiness_path: None,
+            }
+        );
+        assert_eq!(
+            parse(vec![("health-path", serde_json::json!("/mcpz"))])
+                .unwrap()
+                .liveness_path,
+            Some("/mcpz".to_string())
+        );
+        // A metrics endpoint on its own port does not share the MCP listener.
+        assert_eq!(
+            parse(vec![
+                ("metrics-path", serde_json::json!("/healthz")),
+                ("metrics-port", serde_json::json!(9464)),
+            ])
+            .unwrap(),
+            HealthConfig::default()
+        );
+        for (extra, expected) in [
+            (
+                vec![("health-path", serde_json::json!("healthz"))],
+                "'health-path' must be a path",
+            ),
+            (
+                vec![("ready-path", serde_json::json!(true))],
+                "'ready-path' must be a path",
+            ),
+            (
+                vec![("health-path", serde_json::json!("/mcp/health"))],
+                "'health-path' must be a path",
+            ),
+            (
+                vec![(
+                    "ready-path",
+                    serde_json::json!("/.well-known/oauth-protected-resource/mcp"),
+                )],
+                "'ready-path' must be a path",
+            ),
+            (
+                vec![("ready-path", serde_json::json!("/healthz"))],
+                "'ready-path' is already used by 'health-path'",
+            ),
+            (
+                vec![("metrics-path", serde_json::json!("/healthz"))],
+                "'health-path' is already used by 'metrics-path'",
+            ),
+            (
+                vec![("metrics-path", serde_json::json!("/readyz"))],
+                "'ready-path' is already used by 'metrics-path'",
+            ),
+        ] {
+            let err = parse(extra).unwrap_err().to_string();
+            assert!(err.contains(expected), "unexpected error: {err}");
+        }
+    }
+
+    #[test]
+    fn parse_audit_config() {
+        let parse = |audit: serde_json::Value, transport: &str| {
+            let (mut handler, config) = make_handler();
+            handler
+                .handle_category(
+                    "server",
+                    "mcp",
+                    props(vec![
+                        ("type", serde_json::json!("mcp")),
+                        ("port", serde_json::json!(3001)),
+                        ("transport", serde_json::json!(transport)),
+                        ("component-selector", serde_json::json!("!dependents")),
+                        ("audit", audit),
+                    ]),
+                )
+                .map(|_| config.lock().unwrap()[0].audit.clone())
+        };
+
+        assert_eq!(
+            parse(
+                serde_json::json!({ "output": "/var/log/toolbelt/audit.jsonl", "redact": ["/password"] }),
+                "streamable-http"
+            )
+            .unwrap(),
+            Some(AuditConfig {
+                output: AuditOutput::File(PathBuf::from("/var/log/toolbelt/audit.jsonl")),
+                redact: vec!["/password".to_string()],
+            })
+        );
+        assert_eq!(
+            parse(serde_json::json!({ "output": "stdout" }), "streamable-http").unwrap(),
+            Some(AuditConfig {
+                output: AuditOutput::Stdout,
+                redact: Vec::new(),
+            })
+        );
+        for (audit, transport, expected) in [
+            (
+                serde_json::json!({}),
+                "streamable-http",
+                "requires 'output'",
+            ),
+            (
+                serde_json::json!({ "output": "stdout", "redact": ["password"] }),
+                "streamable-http",
+                "JSON pointers",
+            ),
+            (
+                serde_json::json!({ "output": "stdout", "format": "csv" }),
+                "streamable-http",
+                "unknown properties",
+            ),
+            (
+                serde_json::json!({ "output": "stdout" }),
+                "stdio",
+                "cannot be 'stdout'",
+            ),
+        ] {
+            let err = parse(audit, transport).unwrap_err().to_string();
+            assert!(err.contains(expected), "unexpected error: {err}");
+        }
+    }
+
+    #[test]
+    fn invalid_tool_naming() {
+        for (naming, expected) in [
+            (serde_json::json!({ "prefix": "acme.tools" }), "'prefix'"),
+            (serde_json::json!({ "separator": "." }), "'separator'"),
+            (serde_json::json!({ "case": "pascal" }), "'case'"),
+            (serde_json::json!({ "max-length": 65 }), "'max-length'"),
+            (serde_json::json!({ "suffix": "x" }), "unknown properties"),
+        ] {
+            let (mut handler, _config) = make_handler();
+            let properties = props(vec![
+                ("type", serde_json::json!("mcp")),
+                ("port", serde_json::json!(3001)),
+                ("component-selector", serde_json::json!("!dependents")),
+                ("tool-naming", naming),
+            ]);
+            let err = handler
+                .handle_category("server", "mcp", properties)
+                .unwrap_err()
+                .to_string();
+            assert!(err.contains(expected), "unexpected error: {err}");
+        }
+    }
+
+    #[test]
+    fn parse_server_and_tool_timeouts() {
+        let (mut handler, config) = make_handler();
+        let properties = props(vec![
+            ("type", serde_json::json!("mcp")),
+            ("port", serde_json::json!(3001)),
+            ("timeout", serde_json::json!(30)),
+            (
+                "tool",
+                serde_json::json!({
+                    "slow": {
+                        "component": "reports",
+                        "function": "generate",
+                        "timeout": "2m"
+                    },
+                    "quick": {
+                        "component": "calculator",
+                        "function": "add",
+                        "timeout": "500ms"
+                    }
+                }),
+            ),
+        ]);
+
+        handler
+            .handle_category("server", "mcp", properties)
+            .unwrap();
+
+        let servers = config.lock().unwrap();
+        assert_eq!(servers[0].timeout, Some(Duration::from_secs(30)));
+        let timeout_of = |name: &str| {
+            servers[0]
+                .tools
+                .iter()
+                .find(|t| t.name == name)
+                .unwrap()
+                .timeout
+        };
+        assert_eq!(timeout_of("slow"), Some(Duration::from_secs(120)));
+        assert_eq!(timeout_of("quick"), Some(Duration::from_millis(500)));
+    }
+
+    #[test]
+    fn invalid_timeout() {
+        for timeout in [
+            serde_json::json!(0),
+            serde_json::json!("soon"),
+            serde_json::json!(true),
+        ] {
+            let (mut handler, _) = make_handler();
+            let properties = props(vec![
+                ("type", serde_json::json!("mcp")),
+                ("port", serde_json::json!(3001)),
+                ("timeout", timeout),
+                ("component-selector", serde_json::json!("!dependents")),
+            ]);
+
+            let err = handler
+                .handle_category("server", "mcp", properties)
+                .unwrap_err()
+                .to_string();
+            assert!(err.contains("'timeout' must be"), "unexpected error: {err}");
+        }
+    }
+
+    #[test]
+    fn parse_rate_limits() {
+        let (mut handler, config) = make_handler();
+        let properties = props(vec![
+            ("type", serde_json::json!("mcp")),
+            ("port", serde_json::json!(3001)),
+            ("rate-limit", serde_json::json!({ "requests": 10 })),
+            (
+                "tool",
+                serde_json::json!({
+                    "report": {
+                        "component": "reports",
+                        "function": "generate",
+                        "rate-limit": { "requests": 5, "per": "1m", "burst": 2 }
+                    }
+                }),
+            ),
+        ]);
+
+        handler
+            .handle_category("server", "mcp", properties)
+            .unwrap();
+
+        let servers = config.lock().unwrap();
+        assert_eq!(
+            servers[0].rate_limit,
+            Some(RateLimitConfig {
+                requests: 10,
+                per: Duration::from_secs(1),
+                burst: 10,
+            })
+        );
+        assert_eq!(
+            servers[0].tools[0].rate_limit,
+            Some(RateLimitConfig {
+                requests: 5,
+                per: Duration::from_secs(60),
+                burst: 2,
+            })
+        );
+    }
+
+    #[test]
+    fn invalid_rate_limit() {
+        for (rate_limit, expected) in [
+            (serde_json::json!(10), "must be a table"),
+            (serde_json::json!({ "per": "1s" }), "requires 'requests'"),
+            (serde_json::json!({ "requests": 0 }), "'requests' must be"),
+            (
+                serde_json::json!({ "requests": 1, "per": "soon" }),
+                "'per' must be",
+            ),
+            (
+                serde_json::json!({ "requests": 1, "burst": -1 }),
+                "'burst' must be",
+            ),
+            (
+                serde_json::json!({ "requests": 1, "window": "1s" }),
+                "unknown properties",
+            ),
+        ] {
+            let (mut handler, _) = make_handler();
+            let properties = props(vec![
+                ("type", serde_json::json!("mcp")),
+                ("port", serde_json::json!(3001)),
+                ("rate-limit", rate_limit),
+                ("component-selector", serde_json::json!("!dependents")),
+            ]);
+
+            let err = handler
+                .handle_category("server", "mcp", properties)
+                .unwrap_err()
+                .to_string();
+            assert!(
+                err.contains("'rate-limit'") && err.contains(expected),
+                "unexpected error: {err}"
+            );
+        }
+    }
+
+    #[test]
+    fn parse_component_output_schema() {
+        let (mut handler, config) = make_handler();
+        let properties = props(vec![
+            ("type", serde_json::json!("mcp")),
+            ("port", serde_json::json!(3001)),
+            (
+                "tool",
+                serde_json::json!({
+                    "lookup": {
+                        "component": "users",
+                        "function": "get",
+                        "output-schema": {
+                            "type": "object",
+                            "properties": { "email": { "type": "string", "format": "email" } }
+                        }
+                    }
+                }),
+            ),
+        ]);
+
+        handler
+            .handle_category("server", "mcp", properties)
+            .unwrap();
+
+        let servers = config.lock().unwrap();
+        let ToolTarget::Component { output_schema, .. } = &servers[0].tools[0].target else {
+            panic!("expected a component tool");
+        };
+        assert_eq!(
+            output_schema.as_ref().unwrap()["properties"]["email"]["format"],
+            "email"
+        );
+    }
+
+    #[test]
+    fn parse_defaults_and_fixed() {
+        let (mut handler, config) = make_handler();
+        let properties = props(vec![
+            ("type", serde_json::json!("mcp")),
+            ("port", serde_json::json!(3001)),
+            (
+                "tool",
+                serde_json::json!({
+                    "search": {
+                        "component": "flights",
+                        "function": "search",
+                        "defaults": { "max-results": 10 },
+                        "fixed": { "region": "eu" }
+                    }
+                }),
+            ),
+        ]);
+
+        handler
+            .handle_category("server", "mcp", properties)
+            .unwrap();
+
+        let servers = config.lock().unwrap();
+        let ToolTarget::Component {
+            defaults, fixed, ..
+        } = &servers[0].tools[0].target
+        else {
+            panic!("expected a component tool");
+        };
+        assert_eq!(defaults["max-results"], 10);
+        assert_eq!(fixed["region"], "eu");
+    }
+
+    #[test]
+    fn param_in_defaults_and_fixed() {
+        let (mut handler, _) = make_handler();
+        let properties = props(vec![
+            ("type", serde_json::json!("mcp")),
+            ("port", serde_json::json!(3001)),
+            (
+                "tool",
+                serde_json::json!({
+                    "search": {
+                        "component": "flights",
+                        "function": "search",
+                        "defaults": { "region": "us" },
+                        "fixed": { "region": "eu" }
+                    }
+                }),
+            ),
+        ]);
+
+        let err = handler
+            .handle_category("server", "mcp", properties)
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("parameter 'region' cannot be in both 'defaults' and 'fixed'"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn parse_tool_annotations() {
+        let (mut handler, config) = make_handler();
+        let properties = props(vec![
+            ("type", serde_json::json!("mcp")),
+            ("port", serde_json::json!(3001)),
+            (
+                "tool",
+                serde_json::json!({
+                    "delete-user": {
+                        "component": "users",
+                        "function": "delete",
+                        "annotations": {
+                            "title": "Delete user",
+                            "destructive-hint": true,
+                            "idempotent-hint": true,
+                            "open-world-hint": false
+                        }
+                    }
+                }),
+            ),
+        ]);
+
+        handler
+            .handle_category("server", "mcp", properties)
+            .unwrap();
+
+        let servers = config.lock().unwrap();
+        let annotations = servers[0].tools[0].annotations.as_ref().unwrap();
+        assert_eq!(annotations.title.as_deref(), Some("Delete user"));
+        assert_eq!(annotations.read_only_hint, None);
+        assert_eq!(annotations.destructive_hint, Some(true));
+        assert_eq!(annotations.idempotent_hint, Some(true));
+        assert_eq!(annotations.open_world_hint, Some(false));
+    }
+
+    #[test]
+    fn contradictory_annotations() {
+        let (mut handler, _) = make_handler();
+        let properties = props(vec![
+            ("type", serde_json::json!("mcp")),
+            ("port", serde_json::json!(3001)),
+            (
+                "tool",
+                serde_json::json!({
+                    "lookup": {
+                        "component": "users",
+                        "function": "get",
+                        "annotations": { "read-only-hint": true, "destructive-hint": false }
+                    }
+                }),
+            ),
+        ]);
+
+        let err = handler
+            .handle_category("server", "mcp", properties)
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("'destructive-hint' cannot be combined with 'read-only-hint = true'"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn annotations_from_labels() {
+        let labels = HashMap::from([
+            ("domain".to_string(), "users".to_string()),
+            ("read-only-hint".to_string(), "true".to_string()),
+        ]);
+        let annotations = ToolAnnotationsConfig::from_labels("users", &labels)
+            .unwrap()
+            .unwrap();
+        assert_eq!(annotations.read_only_hint, Some(true));
+
+        let merged = ToolAnnotationsConfig {
+            open_world_hint: Some(true),
+            read_only_hint: Some(false),
+            ..Default::default()
+        }
+        .or(annotations);
+        assert_eq!(merged.read_only_hint, Some(false));
+        assert_eq!(merged.open_world_hint, Some(true));
+
+        let unlabeled = HashMap::from([("domain".to_string(), "users".to_string())]);
+        assert!(
+            ToolAnnotationsConfig::from_labels("users", &unlabeled)
+                .unwrap()
+                .is_none()
+        );
+
+        let invalid = HashMap::from([("open-world-hint".to_string(), "yes".to_string())]);
+        assert!(ToolAnnotationsConfig::from_labels("users", &invalid).is_err());
+    }
+
+    #[test]
+    fn parse_api_key_auth() {
+        let (mut handler, config) = make_handler();
+        let properties = props(vec![
+            ("type", serde_json::json!("mcp")),
+            ("port", serde_json::json!(3001)),
+            ("component-selector", serde_json::json!("!dependents")),
+            (
+                "auth",
+                serde_json::json!({
+                    "scheme": "api-key",
+                    "credentials": [
+                        { "principal": "ci", "env": "CI_MCP_KEY" },
+                        { "principal": "alice", "file": "/run/secrets/alice" }
+                    ]
+                }),
+            ),
+        ]);
+
+        handler
+            .handle_category("server", "mcp", properties)
+            .unwrap();
+
+        let servers = config.lock().unwrap();
+        let auth = servers[0].auth.as_ref().unwrap();
+        assert_eq!(
+            auth.scheme,
+            AuthScheme::ApiKey {
+                header: "X-API-Key".to_string()
+            }
+        );
+        assert_eq!(auth.credentials.len(), 2);
+        assert_eq!(auth.credentials[0].principal, "ci");
+        assert_eq!(
+            auth.credentials[0].secret,
+            SecretSource::Env("CI_MCP_KEY".to_string())
+        );
+        assert_eq!(
+            auth.credentials[1].secret,
+            SecretSource::File(PathBuf::from("/run/secrets/alice"))
+        );
+    }
+
+    #[test]
+    fn auth_credential_requires_one_source() {
+        let (mut handler, _) = make_handler();
+        let properties = props(vec![
+            ("type", serde_json::json!("mcp")),
+            ("port", serde_json::json!(3001)),
+            ("component-selector", serde_json::json!("!dependents")),
+            (
+                "auth",
+                serde_json::json!({
+                    "credentials": [
+                        { "principal": "ci", "env": "A", "file": "/b" }
+                    ]
+                }),
+            ),
+        ]);
+
+        let result = handler.handle_category("server", "mcp", properties);
+        assert!(result.is_err());
+        let err = result.unwrap_err().to_string();
+        assert!(
+            err.contains("exactly one of 'env' or 'file'"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn parse_oauth_auth_and_tool_scopes() {
+        let (mut handler, config) = make_handler();
+        let properties = props(vec![
+            ("type", serde_json::json!("mcp")),
+            ("port", serde_json::json!(3001)),
+            (
+                "auth",
+                serde_json::json!({
+                    "scheme": "oauth",
+                    "issuer": "https://idp.example.com",
+                    "audience": "https://mcp.example.com/mcp",
+                    "jwks": "https://idp.example.com/.well-known/jwks.json"
+                }),
+            ),
+            (
+                "tool",
+                serde_json::json!({
+                    "delete-user": {
+                        "component": "users",
+                        "function": "delete",
+                        "scopes": ["users:write"]
+                    }
+                }),
+            ),
+        ]);
+
+        handler
+            .handle_category("server", "mcp", properties)
+            .unwrap();
+
+        let servers = config.lock().unwrap();
+        let Some(AuthConfig {
+            scheme: AuthScheme::OAuth(oauth),
+            ..
+        }) = &servers[0].auth
+        else {
+            panic!("expected oauth scheme");
+        };
+        assert_eq!(oauth.issuer, "https://idp.example.com");
+        assert_eq!(oauth.resource, "https://mcp.example.com/mcp");
+        assert_eq!(
+            oauth.jwks,
+            JwksSource::Url("https://idp.example.com/.well-known/jwks.json".to_string())
+        );
+        assert_eq!(oauth.authorization_servers, ["https://idp.example.com"]);
+        assert_eq!(servers[0].tools[0].scopes, ["users:write"]);
+    }
+
+    #[test]
+    fn tool_scopes_require_oauth() {
+        for auth in [
+            None,
+            Some(serde_json::json!({
+                "scheme": "bearer",
+                "credentials": [{ "principal": "ci", "env": "TOOLBELT_CI_TOKEN" }]
+            })),
+        ] {
+            let (mut handler, _) = make_handler();
+            let mut entries = vec![
+                ("type", serde_json::json!("mcp")),
+                ("port", serde_json::json!(3001)),
+                (
+                    "tool",
+                    serde_json::json!({
+                        "delete-user": {
+                            "component": "users",
+                            "function": "delete",
+                            "scopes": ["users:write"]
+                        }
+                    }),
+                ),
+            ];
+            if let Some(auth) = auth {
+                entries.push(("auth", auth));
+            }
+
+            let result = handler.handle_category("server", "mcp", props(entries));
+            let err = result.unwrap_err().to_string();
+            assert!(
+                err.contains("tool 'delete-user' 'scopes' require auth scheme 'oauth'"),
+                "unexpected error: {err}"
+            );
+        }
+    }
+
+    #[test]
+    fn oauth_requires_jwks() {
+        let (mut handler, _) = make_handler();
+        let properties = props(vec![
+            ("type", serde_json::json!("mcp")),
+            ("port", serde_json::json!(3001)),
+            ("component-selector", serde_json::json!("!dependents")),
+            (
+                "auth",
+                serde_json::json!({
+                    "scheme": "oauth",
+                    "issuer": "https://idp.example.com",
+                    "audience": "mcp"
+                }),
+            ),
+        ]);
+
+        let result = handler.handle_category("server", "mcp", properties);
+        assert!(result.is_err());
+        let err = result.unwrap_err().to_string();
+        assert!(
+            err.contains("requires 'issuer', 'audience' and 'jwks'"),
+            "unexpected error: {err}"
+        );
+    }
 }
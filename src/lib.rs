@@ -1,7 +1,18 @@
+mod audit;
+mod auth;
+mod commands;
 mod config;
 mod mapper;
+mod metrics;
+mod oauth;
 mod origin;
+mod progress;
+mod ratelimit;
+mod reload;
+mod resource;
 mod server;
 mod service;
 
+pub use commands::{ToolListing, call_tool, validate};
+pub use progress::{PROGRESS_CAPABILITY, ProgressReport, ProgressReporter};
 pub use service::McpService;
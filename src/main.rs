@@ -1,23 +1,79 @@
 use anyhow::Result;
-use clap::Parser;
+use clap::{Parser, Subcommand, ValueEnum};
 use std::path::PathBuf;
 
-use composable_mcp::McpService;
+use composable_mcp::{McpService, PROGRESS_CAPABILITY, ToolListing, call_tool, validate};
 use composable_otel::OtelService;
 use composable_runtime::Runtime;
 
 #[derive(Parser)]
 #[command(name = "toolbelt")]
 #[command(about = "Modulewise Toolbelt is an MCP Server for Wasm Components")]
+#[command(args_conflicts_with_subcommands = true)]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Component definition files (.toml) and standalone .wasm files
     #[arg(help = "Component definition files (.toml) and standalone .wasm files")]
     definitions: Vec<PathBuf>,
+
+    /// Reload tools when definition files change
+    #[arg(long)]
+    watch: bool,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Check definition files and resolve every MCP server without serving
+    Validate {
+        /// Component definition files (.toml) and standalone .wasm files
+        #[arg(required = true)]
+        definitions: Vec<PathBuf>,
+    },
+    /// Print the tools each MCP server publishes, as clients will see them
+    Tools {
+        /// Component definition files (.toml) and standalone .wasm files
+        #[arg(required = true)]
+        definitions: Vec<PathBuf>,
+
+        /// Only list tools of this server
+        #[arg(long)]
+        server: Option<String>,
+
+        #[arg(long, value_enum, default_value_t = Format::Json)]
+        format: Format,
+    },
+    /// Call a tool once and print the result, without an MCP client
+    Call {
+        /// Component definition files (.toml) and standalone .wasm files
+        #[arg(required = true)]
+        definitions: Vec<PathBuf>,
+
+        /// Name of the tool to call
+        tool: String,
+
+        /// Tool arguments as a JSON object
+        #[arg(long, default_value = "{}")]
+        args: String,
+
+        /// Server publishing the tool, if more than one does
+        #[arg(long)]
+        server: Option<String>,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Json,
+    Table,
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    // Log to stderr so stdout stays clean for the stdio transport.
     tracing_subscriber::fmt()
+        .with_writer(std::io::stderr)
         .with_env_filter(
             tracing_subscriber::EnvFilter::try_from_default_env()
                 .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info")),
@@ -26,12 +82,76 @@ async fn main() -> Result<()> {
 
     let cli = Cli::parse();
 
+    if let Some(command) = cli.command {
+        return run_command(command).await;
+    }
+
+    let mut mcp_service = McpService::default();
+    if cli.watch {
+        mcp_service = mcp_service.with_watched_definitions(cli.definitions.clone());
+    }
+
+    let progress = mcp_service.progress_reporter();
     let runtime = Runtime::builder()
         .from_paths(&cli.definitions)
+        .with_host_extension_instance(PROGRESS_CAPABILITY, progress)
         .with_service::<OtelService>()
-        .with_service::<McpService>()
+        .with_service_instance(mcp_service)
         .build()
         .await?;
 
     runtime.run().await
 }
+
+// Run an offline subcommand, exiting non-zero when it finds a problem.
+async fn run_command(command: Command) -> Result<()> {
+    match command {
+        Command::Validate { definitions } => {
+            let errors = validate(&definitions).await;
+            if errors.is_empty() {
+                eprintln!("Definitions are valid");
+                return Ok(());
+            }
+            for error in &errors {
+                eprintln!("error: {error}");
+            }
+            eprintln!(
+                "Found {} {}",
+                errors.len(),
+                if errors.len() == 1 { "error" } else { "errors" }
+            );
+            std::process::exit(1);
+        }
+        Command::Tools {
+            definitions,
+            server,
+            format,
+        } => {
+            let listing = ToolListing::resolve(&definitions, server.as_deref()).await?;
+            match format {
+                Format::Json => println!("{}", serde_json::to_string_pretty(&listing.to_json())?),
+                Format::Table => print!("{}", listing.to_table()),
+            }
+            Ok(())
+        }
+        Command::Call {
+            definitions,
+            tool,
+            args,
+            server,
+        } => {
+            let arguments = match serde_json::from_str(&args)? {
+                serde_json::Value::Object(arguments) => arguments,
+                got => {
+                    return Err(anyhow::anyhow!("--args must be a JSON object, got {got}"));
+                }
+            };
+            let result = call_tool(&definitions, server.as_deref(), &tool, arguments).await?;
+            println!("{}", serde_json::to_string_pretty(&result)?);
+            if result.is_error == Some(true) {
+                std::process::exit(1);
+            }
+            Ok(())
+        }
+    }
+}
@@ -1,9 +1,17 @@
-use rmcp::model::Tool;
+use rmcp::model::{Meta, Prompt, PromptArgument, Tool, ToolAnnotations};
 use serde_json::json;
 
 use composable_runtime::Function;
 
-/// Mapper that converts core types to MCP Tools
+use crate::config::{NameCase, ToolAnnotationsConfig, ToolNamingConfig};
+
+/// Tool `_meta` key documenting the structured content of an error result.
+pub const ERROR_SCHEMA_META_KEY: &str = "errorSchema";
+
+// Cases of the `content-block` variant in the `composable:mcp/content` interface.
+const CONTENT_BLOCK_CASES: [&str; 5] = ["text", "image", "audio", "resource-link", "resource"];
+
+/// Mapper that converts core types to MCP Tools and Prompts
 pub struct McpMapper;
 
 impl McpMapper {
@@ -16,35 +24,16 @@ impl McpMapper {
         tool_name: &str,
         description: Option<&str>,
     ) -> Tool {
+        let (docs, _) = Self::split_param_docs(function);
         let description = if let Some(desc) = description {
             desc.to_string()
-        } else if function.docs().is_empty() {
+        } else if docs.is_empty() {
             format!("Call {} function", function.function_name())
         } else {
-            function.docs().to_string()
+            docs
         };
 
-        let mut properties = serde_json::Map::new();
-        let mut required = Vec::new();
-
-        for param in function.params() {
-            let mut param_schema = if param.is_optional {
-                Self::flatten_schema_if_possible(&param.json_schema)
-            } else {
-                param.json_schema.clone()
-            };
-
-            if let serde_json::Value::Object(ref mut schema_obj) = param_schema {
-                schema_obj.insert(
-                    "description".to_string(),
-                    serde_json::Value::String(format!("Parameter: {}", param.name)),
-                );
-            }
-            properties.insert(param.name.clone(), param_schema);
-            if !param.is_optional {
-                required.push(param.name.clone());
-            }
-        }
+        let (properties, required) = Self::param_properties(function);
 
         let input_schema = json!({
             "type": "object",
@@ -64,9 +53,82 @@ impl McpMapper {
             tool = tool.with_raw_output_schema(output_schema.into());
         }
 
+        if let Some(error_schema) = Self::create_error_schema(function) {
+            let mut meta = Meta::new();
+            meta.insert(
+                ERROR_SCHEMA_META_KEY.to_string(),
+                serde_json::Value::Object(error_schema),
+            );
+            tool.meta = Some(meta);
+        }
+
         tool
     }
 
+    /// True if the function returns the well-known `content-block` type, or a
+    /// list of them, whose values are returned as native MCP content blocks.
+    pub fn returns_content(function: &Function) -> bool {
+        function.result().is_some_and(|schema| {
+            let schema = schema
+                .as_object()
+                .and_then(|obj| Self::extract_result_arm(obj, "ok"))
+                .unwrap_or(schema);
+            let block = match schema.get("type").and_then(|t| t.as_str()) {
+                Some("array") => schema.get("items"),
+                _ => Some(schema),
+            };
+            block.is_some_and(Self::is_content_block)
+        })
+    }
+
+    /// True if the function returns `result<T, E>` with a structured `E`, such
+    /// as a variant, record or enum, whose errors are returned as structured content.
+    pub fn has_structured_error(function: &Function) -> bool {
+        function
+            .result()
+            .and_then(|schema| schema.as_object())
+            .and_then(|obj| Self::extract_result_arm(obj, "error"))
+            .is_some_and(Self::is_structured)
+    }
+
+    /// Convert a Function to an MCP Prompt.
+    ///
+    /// Prompt arguments are derived from the function's params.
+    /// `description` overrides the function's docs when provided.
+    pub fn function_to_prompt(
+        function: &Function,
+        prompt_name: &str,
+        description: Option<&str>,
+    ) -> Prompt {
+        let (docs, _) = Self::split_param_docs(function);
+        let description = if let Some(desc) = description {
+            Some(desc.to_string())
+        } else if docs.is_empty() {
+            None
+        } else {
+            Some(docs)
+        };
+
+        // Iterate params rather than properties to keep declaration order.
+        let (properties, required) = Self::param_properties(function);
+        let arguments = function
+            .params()
+            .iter()
+            .map(|param| PromptArgument {
+                name: param.name.clone(),
+                title: None,
+                description: properties
+                    .get(&param.name)
+                    .and_then(|schema| schema.get("description"))
+                    .and_then(|d| d.as_str())
+                    .map(|d| d.to_string()),
+                required: Some(required.contains(&param.name)),
+            })
+            .collect();
+
+        Prompt::new(prompt_name, description, Some(arguments))
+    }
+
     /// Create an MCP Tool from a channel config with explicit input and optional output schemas.
     pub fn channel_tool(
         tool_name: &str,
@@ -97,6 +159,259 @@ impl McpMapper {
         tool
     }
 
+    /// Convert configured tool annotations to their MCP form.
+    pub fn tool_annotations(config: &ToolAnnotationsConfig) -> ToolAnnotations {
+        let mut annotations = ToolAnnotations::new();
+        annotations.title = config.title.clone();
+        annotations.read_only_hint = config.read_only_hint;
+        annotations.destructive_hint = config.destructive_hint;
+        annotations.idempotent_hint = config.idempotent_hint;
+        annotations.open_world_hint = config.open_world_hint;
+        annotations
+    }
+
+    /// Name a selector-discovered tool so it matches `^[a-zA-Z0-9_-]{1,64}$`.
+    pub fn tool_name(
+        naming: &ToolNamingConfig,
+        component_name: &str,
+        function_key: &str,
+    ) -> String {
+        let mut name = naming
+            .prefix
+            .as_deref()
+            .into_iter()
+            .chain([component_name, function_key])
+            .map(|part| Self::convert_case(part, naming.case))
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join(&naming.separator);
+        // Every character is ASCII, so truncating by bytes is safe.
+        name.truncate(naming.max_length);
+        name
+    }
+
+    fn convert_case(part: &str, case: NameCase) -> String {
+        if case == NameCase::Preserve {
+            return part
+                .chars()
+                .map(|c| {
+                    if c.is_ascii_alphanumeric() || c == '-' {
+                        c
+                    } else {
+                        '_'
+                    }
+                })
+                .collect();
+        }
+        let words = Self::name_words(part);
+        match case {
+            NameCase::Snake => words.join("_"),
+            NameCase::Kebab => words.join("-"),
+            _ => words
+                .iter()
+                .enumerate()
+                .map(|(i, word)| {
+                    let mut chars = word.chars();
+                    match chars.next() {
+                        Some(first) if i > 0 => {
+                            first.to_ascii_uppercase().to_string() + chars.as_str()
+                        }
+                        _ => word.clone(),
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    // Split on non-alphanumeric characters and lower-to-upper case changes,
+    // lowercasing each word: `wasi:http/getUser` -> `wasi`, `http`, `get`, `user`.
+    fn name_words(part: &str) -> Vec<String> {
+        let mut words = Vec::new();
+        let mut word = String::new();
+        let mut prev_lower = false;
+        for c in part.chars() {
+            let boundary = !c.is_ascii_alphanumeric() || (c.is_ascii_uppercase() && prev_lower);
+            if boundary && !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            if c.is_ascii_alphanumeric() {
+                word.push(c.to_ascii_lowercase());
+            }
+            prev_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+        }
+        if !word.is_empty() {
+            words.push(word);
+        }
+        words
+    }
+
+    /// Hide `fixed` parameters from an input schema and publish `defaults`.
+    ///
+    /// Both kinds of parameter become optional, since the server supplies them.
+    pub fn apply_argument_overrides(
+        input_schema: &mut rmcp::model::JsonObject,
+        defaults: &serde_json::Map<String, serde_json::Value>,
+        fixed: &serde_json::Map<String, serde_json::Value>,
+    ) {
+        if let Some(properties) = input_schema
+            .get_mut("properties")
+            .and_then(|p| p.as_object_mut())
+        {
+            for name in fixed.keys() {
+                properties.remove(name);
+            }
+            for (name, value) in defaults {
+                if let Some(schema) = properties.get_mut(name).and_then(|s| s.as_object_mut()) {
+                    schema.insert("default".to_string(), value.clone());
+                }
+            }
+        }
+        if let Some(required) = input_schema
+            .get_mut("required")
+            .and_then(|r| r.as_array_mut())
+        {
+            required.retain(|name| {
+                name.as_str()
+                    .is_none_or(|name| !defaults.contains_key(name) && !fixed.contains_key(name))
+            });
+        }
+    }
+
+    /// Check that an explicit output schema only narrows the WIT-derived one.
+    ///
+    /// Every type it declares must match the derived type, and every property it
+    /// declares must exist in the derived schema. Finer constraints such as formats
+    /// or enums are left to validating each result.
+    pub fn check_output_schema(
+        explicit: &serde_json::Value,
+        derived: &serde_json::Value,
+    ) -> Result<(), String> {
+        Self::check_schema_narrows(explicit, derived, "")
+    }
+
+    fn check_schema_narrows(
+        explicit: &serde_json::Value,
+        derived: &serde_json::Value,
+        path: &str,
+    ) -> Result<(), String> {
+        let location = if path.is_empty() {
+            "output-schema".to_string()
+        } else {
+            format!("output-schema property '{path}'")
+        };
+        let explicit_type = explicit.get("type").and_then(|t| t.as_str());
+        let derived_type = derived.get("type").and_then(|t| t.as_str());
+        if let (Some(explicit_type), Some(derived_type)) = (explicit_type, derived_type) {
+            let numeric = |t: &str| t == "integer" || t == "number";
+            if explicit_type != derived_type && !(numeric(explicit_type) && numeric(derived_type)) {
+                return Err(format!(
+                    "{location} has type '{explicit_type}' but the function returns '{derived_type}'"
+                ));
+            }
+        }
+        if let Some(properties) = explicit.get("properties").and_then(|p| p.as_object()) {
+            let derived_properties = derived.get("properties").and_then(|p| p.as_object());
+            for (name, schema) in properties {
+                let property_path = if path.is_empty() {
+                    name.clone()
+                } else {
+                    format!("{path}.{name}")
+                };
+                let Some(derived_schema) = derived_properties.and_then(|p| p.get(name)) else {
+                    return Err(format!(
+                        "output-schema property '{property_path}' is not in the function's result"
+                    ));
+                };
+                Self::check_schema_narrows(schema, derived_schema, &property_path)?;
+            }
+        }
+        if let (Some(items), Some(derived_items)) = (explicit.get("items"), derived.get("items")) {
+            Self::check_schema_narrows(items, derived_items, &format!("{path}[]"))?;
+        }
+        Ok(())
+    }
+
+    // Build JSON Schema properties and the required list from a function's params.
+    fn param_properties(
+        function: &Function,
+    ) -> (serde_json::Map<String, serde_json::Value>, Vec<String>) {
+        let mut properties = serde_json::Map::new();
+        let mut required = Vec::new();
+
+        for param in function.params() {
+            let param_schema = if param.is_optional {
+                Self::flatten_schema_if_possible(&param.json_schema)
+            } else {
+                param.json_schema.clone()
+            };
+            properties.insert(param.name.clone(), param_schema);
+            if !param.is_optional {
+                required.push(param.name.clone());
+            }
+        }
+
+        // Record fields keep the descriptions the runtime derives from their
+        // WIT doc comments; parameters themselves are documented by the function.
+        let (_, param_docs) = Self::split_param_docs(function);
+        for (name, doc) in param_docs {
+            if let Some(schema) = properties.get_mut(&name).and_then(|s| s.as_object_mut()) {
+                schema.insert("description".to_string(), serde_json::Value::String(doc));
+            }
+        }
+
+        (properties, required)
+    }
+
+    // Split a function's doc comment into its description and parameter docs.
+    //
+    // WIT doc comments cannot be attached to parameters, so a parameter is
+    // documented in the function's doc comment by a `@param name text` line or
+    // a `- name: text` list item naming it. Indented lines that follow an entry
+    // continue it. These lines are removed from the description.
+    fn split_param_docs(function: &Function) -> (String, Vec<(String, String)>) {
+        let names: Vec<&str> = function.params().iter().map(|p| p.name.as_str()).collect();
+        Self::split_docs(function.docs(), &names)
+    }
+
+    fn split_docs(docs: &str, param_names: &[&str]) -> (String, Vec<(String, String)>) {
+        let mut description: Vec<&str> = Vec::new();
+        let mut entries: Vec<(String, String)> = Vec::new();
+        let mut continuing = false;
+        for line in docs.lines() {
+            let trimmed = line.trim();
+            let entry = if let Some(rest) = trimmed.strip_prefix("@param ") {
+                rest.trim_start().split_once(char::is_whitespace)
+            } else if let Some(rest) = trimmed
+                .strip_prefix("- ")
+                .or_else(|| trimmed.strip_prefix("* "))
+            {
+                rest.split_once(':')
+            } else {
+                None
+            };
+            let entry = entry
+                .map(|(name, doc)| (name.trim().trim_matches('`'), doc.trim()))
+                .filter(|(name, doc)| param_names.contains(name) && !doc.is_empty());
+            if let Some((name, doc)) = entry {
+                entries.push((name.to_string(), doc.to_string()));
+                continuing = true;
+            } else if continuing && !trimmed.is_empty() && line.starts_with(char::is_whitespace) {
+                let (_, doc) = entries.last_mut().expect("continuing an entry");
+                doc.push(' ');
+                doc.push_str(trimmed);
+            } else {
+                continuing = false;
+                description.push(line);
+            }
+        }
+        // Drop blank lines left where the parameter docs were.
+        let mut description = description.join("\n");
+        while description.contains("\n\n\n") {
+            description = description.replace("\n\n\n", "\n\n");
+        }
+        (description.trim().to_string(), entries)
+    }
+
     fn flatten_schema_if_possible(schema: &serde_json::Value) -> serde_json::Value {
         if let Some(one_of) = schema.get("oneOf").and_then(|v| v.as_array())
             && one_of.len() == 2
@@ -120,9 +435,36 @@ impl McpMapper {
     }
 
     fn create_output_schema(function: &Function) -> Option<rmcp::model::JsonObject> {
+        if Self::returns_content(function) {
+            return None;
+        }
         function.result().and_then(Self::output_schema_for_type)
     }
 
+    // Schema of an error result's structured content: `{ "error": E }`.
+    fn create_error_schema(function: &Function) -> Option<rmcp::model::JsonObject> {
+        if !Self::has_structured_error(function) {
+            return None;
+        }
+        let obj = function.result()?.as_object()?;
+        let error_type = Self::extract_result_arm(obj, "error")?;
+        let wrapped = json!({
+            "type": "object",
+            "properties": { "error": error_type },
+            "required": ["error"],
+            "additionalProperties": false
+        });
+        Some(wrapped.as_object().unwrap().clone())
+    }
+
+    // A bare string or number error stays plain text; anything else is structured.
+    fn is_structured(schema: &serde_json::Value) -> bool {
+        match schema.get("type").and_then(|t| t.as_str()) {
+            Some("string" | "number" | "integer" | "boolean") => schema.get("enum").is_some(),
+            _ => true,
+        }
+    }
+
     fn output_schema_for_type(schema: &serde_json::Value) -> Option<rmcp::model::JsonObject> {
         let obj = schema.as_object()?;
         if let Some(schema_type) = obj.get("type").and_then(|t| t.as_str()) {
@@ -156,8 +498,9 @@ impl McpMapper {
             }
         } else if obj.get("oneOf").and_then(|a| a.as_array()).is_some() {
             // WIT result<T, E> -> unwrap T (the success arm). An error arm
-            // surfaces at the MCP response level via isError + text content.
-            if let Some(ok_type) = Self::extract_result_ok_type(obj) {
+            // surfaces at the MCP response level via isError, documented by
+            // the tool's error schema when E is structured.
+            if let Some(ok_type) = Self::extract_result_arm(obj, "ok") {
                 return Self::output_schema_for_type(ok_type);
             }
             // WIT variant -> emit as-is. The oneOf already describes a valid
@@ -199,17 +542,38 @@ impl McpMapper {
         })
     }
 
-    // If `obj` is `result<T, E>`, return the inner schema of the `ok` arm.
-    // Otherwise return None.
-    fn extract_result_ok_type(
-        obj: &serde_json::Map<String, serde_json::Value>,
-    ) -> Option<&serde_json::Value> {
+    // True if `schema` is a variant with exactly the `content-block` cases.
+    fn is_content_block(schema: &serde_json::Value) -> bool {
+        let Some(obj) = schema.as_object() else {
+            return false;
+        };
+        if !Self::is_variant_oneof(obj) {
+            return false;
+        }
+        let mut cases: Vec<&str> = obj["oneOf"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|arm| arm["properties"]["type"]["const"].as_str())
+            .collect();
+        cases.sort_unstable();
+        let mut expected = CONTENT_BLOCK_CASES;
+        expected.sort_unstable();
+        cases == expected
+    }
+
+    // If `obj` is `result<T, E>`, return the inner schema of the named arm
+    // (`ok` or `error`). Otherwise return None.
+    fn extract_result_arm<'a>(
+        obj: &'a serde_json::Map<String, serde_json::Value>,
+        arm_name: &str,
+    ) -> Option<&'a serde_json::Value> {
         let arms = obj.get("oneOf")?.as_array()?;
         if arms.len() != 2 {
             return None;
         }
         let mut ok_inner: Option<&serde_json::Value> = None;
-        let mut has_error = false;
+        let mut error_inner: Option<&serde_json::Value> = None;
         for arm in arms {
             let props = arm.get("properties").and_then(|p| p.as_object())?;
             if props.len() != 1 {
@@ -218,11 +582,15 @@ impl McpMapper {
             let (key, value) = props.iter().next()?;
             match key.as_str() {
                 "ok" => ok_inner = Some(value),
-                "error" => has_error = true,
+                "error" => error_inner = Some(value),
                 _ => return None,
             }
         }
-        if has_error { ok_inner } else { None }
+        match arm_name {
+            "ok" if error_inner.is_some() => ok_inner,
+            "error" if ok_inner.is_some() => error_inner,
+            _ => None,
+        }
     }
 
     fn derive_array_property_name(
@@ -271,6 +639,177 @@ mod tests {
         McpMapper::output_schema_for_type(&v)
     }
 
+    #[test]
+    fn param_docs_from_function_docs() {
+        let docs = "Search for flights.\n\
+                    \n\
+                    - `origin`: IATA code of the departure airport\n\
+                    - passengers: travellers,\n  \
+                    grouped by age\n\
+                    @param max-price upper bound in USD\n\
+                    - passengers.adults: not a parameter\n\
+                    \n\
+                    Returns matching flights.";
+        let (description, entries) =
+            McpMapper::split_docs(docs, &["origin", "passengers", "max-price"]);
+        assert_eq!(
+            entries,
+            [
+                (
+                    "origin".to_string(),
+                    "IATA code of the departure airport".to_string()
+                ),
+                (
+                    "passengers".to_string(),
+                    "travellers, grouped by age".to_string()
+                ),
+                ("max-price".to_string(), "upper bound in USD".to_string()),
+            ]
+        );
+        assert_eq!(
+            description,
+            "Search for flights.\n\n- passengers.adults: not a parameter\n\nReturns matching flights."
+        );
+        let (description, entries) = McpMapper::split_docs("Add two numbers.", &["a", "b"]);
+        assert_eq!(description, "Add two numbers.");
+        assert!(entries.is_empty());
+    }
+
+    #[test]
+    fn optional_record_keeps_field_docs() {
+        let schema = json!({
+            "oneOf": [
+                { "type": "null" },
+                {
+                    "type": "object",
+                    "properties": {
+                        "adults": { "type": "integer", "description": "Aged 16 or over" }
+                    }
+                }
+            ]
+        });
+        let flattened = McpMapper::flatten_schema_if_possible(&schema);
+        assert_eq!(
+            flattened["properties"]["adults"]["description"],
+            "Aged 16 or over"
+        );
+    }
+
+    #[test]
+    fn tool_names_follow_naming_config() {
+        let naming = |prefix: Option<&str>, separator: &str, case, max_length| ToolNamingConfig {
+            prefix: prefix.map(|p| p.to_string()),
+            separator: separator.to_string(),
+            case,
+            max_length,
+        };
+        let key = "acme:users/directory.getUser";
+
+        let preserve = naming(None, "_", NameCase::Preserve, 64);
+        assert_eq!(
+            McpMapper::tool_name(&preserve, "user-service", key),
+            "user-service_acme_users_directory_getUser"
+        );
+        let snake = naming(Some("corp"), "__", NameCase::Snake, 64);
+        assert_eq!(
+            McpMapper::tool_name(&snake, "user-service", key),
+            "corp__user_service__acme_users_directory_get_user"
+        );
+        let kebab = naming(None, "-", NameCase::Kebab, 64);
+        assert_eq!(
+            McpMapper::tool_name(&kebab, "UserService", "add"),
+            "user-service-add"
+        );
+        let camel = naming(None, "_", NameCase::Camel, 20);
+        assert_eq!(
+            McpMapper::tool_name(&camel, "user-service", key),
+            "userService_acmeUser"
+        );
+    }
+
+    #[test]
+    fn argument_overrides_hide_fixed_and_publish_defaults() {
+        let mut input_schema = json!({
+            "type": "object",
+            "properties": {
+                "query": { "type": "string" },
+                "region": { "type": "string" },
+                "limit": { "type": "integer" }
+            },
+            "required": ["query", "region", "limit"],
+            "additionalProperties": false
+        })
+        .as_object()
+        .unwrap()
+        .clone();
+        let defaults = json!({ "limit": 10 }).as_object().unwrap().clone();
+        let fixed = json!({ "region": "eu" }).as_object().unwrap().clone();
+
+        McpMapper::apply_argument_overrides(&mut input_schema, &defaults, &fixed);
+
+        assert!(input_schema["properties"].get("region").is_none());
+        assert_eq!(input_schema["properties"]["limit"]["default"], 10);
+        assert_eq!(input_schema["required"], json!(["query"]));
+    }
+
+    #[test]
+    fn output_schema_may_narrow_derived_schema() {
+        let derived = json!({
+            "type": "object",
+            "properties": {
+                "users": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "email": { "type": "string" },
+                            "age": { "type": "number" }
+                        }
+                    }
+                }
+            },
+            "required": ["users"]
+        });
+        let narrowed = json!({
+            "type": "object",
+            "properties": {
+                "users": {
+                    "type": "array",
+                    "maxItems": 10,
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "email": { "type": "string", "format": "email" },
+                            "age": { "type": "integer", "minimum": 0 }
+                        },
+                        "required": ["email"]
+                    }
+                }
+            }
+        });
+        assert!(McpMapper::check_output_schema(&narrowed, &derived).is_ok());
+
+        let wrong_type = json!({
+            "type": "object",
+            "properties": {
+                "users": { "type": "array", "items": { "properties": { "age": { "type": "string" } } } }
+            }
+        });
+        assert_eq!(
+            McpMapper::check_output_schema(&wrong_type, &derived).unwrap_err(),
+            "output-schema property 'users[].age' has type 'string' but the function returns 'number'"
+        );
+
+        let unknown_property = json!({
+            "type": "object",
+            "properties": { "total": { "type": "integer" } }
+        });
+        assert_eq!(
+            McpMapper::check_output_schema(&unknown_property, &derived).unwrap_err(),
+            "output-schema property 'total' is not in the function's result"
+        );
+    }
+
     #[test]
     fn record_passes_through() {
         let input = json!({
@@ -413,6 +952,77 @@ mod tests {
         assert_eq!(out["properties"]["id"]["type"], "string");
     }
 
+    #[test]
+    fn structured_error_arm() {
+        // result<record, variant { not-found(string), rate-limited(u32) }>
+        let variant = json!({
+            "oneOf": [
+                {
+                    "type": "object",
+                    "properties": {
+                        "type": { "const": "not-found" },
+                        "value": { "type": "string" }
+                    }
+                },
+                {
+                    "type": "object",
+                    "properties": {
+                        "type": { "const": "rate-limited" },
+                        "value": { "type": "integer" }
+                    }
+                }
+            ]
+        });
+        let result = json!({
+            "oneOf": [
+                {
+                    "type": "object",
+                    "properties": { "ok": { "type": "object" } },
+                    "required": ["ok"]
+                },
+                {
+                    "type": "object",
+                    "properties": { "error": variant },
+                    "required": ["error"]
+                }
+            ]
+        });
+        let obj = result.as_object().unwrap();
+        let error = McpMapper::extract_result_arm(obj, "error").unwrap();
+        assert_eq!(error, &variant);
+        assert!(McpMapper::is_structured(error));
+        assert!(McpMapper::is_structured(
+            &json!({ "type": "string", "enum": ["denied"] })
+        ));
+        assert!(!McpMapper::is_structured(&json!({ "type": "string" })));
+
+        // An option is not a result, so it has no error arm.
+        let option = json!({ "oneOf": [{ "type": "null" }, { "type": "string" }] });
+        assert!(McpMapper::extract_result_arm(option.as_object().unwrap(), "error").is_none());
+    }
+
+    #[test]
+    fn content_block_variant() {
+        let arm = |case: &str| {
+            json!({
+                "type": "object",
+                "properties": {
+                    "type": { "const": case },
+                    "value": { "type": "object" }
+                }
+            })
+        };
+        let block = json!({
+            "oneOf": ["text", "image", "audio", "resource-link", "resource"]
+                .map(arm)
+        });
+        assert!(McpMapper::is_content_block(&block));
+
+        let shape = json!({ "oneOf": ["text", "image"].map(arm) });
+        assert!(!McpMapper::is_content_block(&shape));
+        assert!(!McpMapper::is_content_block(&json!({ "type": "string" })));
+    }
+
     #[test]
     fn primitive_returns_none() {
         assert!(schema(json!({ "type": "string" })).is_none());
@@ -0,0 +1,380 @@
+use anyhow::Result;
+use opentelemetry::KeyValue;
+use opentelemetry::metrics::{Counter, Histogram, MeterProvider as _, UpDownCounter};
+use opentelemetry_otlp::{MetricExporter, WithExportConfig};
+use opentelemetry_sdk::metrics::{PeriodicReader, SdkMeterProvider};
+use prometheus::{Registry, TextEncoder};
+use std::time::Duration;
+
+use crate::config::PrometheusConfig;
+
+/// Instrumentation scope for all MCP server metrics.
+pub const METER_NAME: &str = "modulewise.composable.mcp.server";
+
+/// `gen_ai.tool.name` recorded for calls to tools the server doesn't have, so
+/// client-chosen names never become metric labels.
+pub const UNKNOWN_TOOL: &str = "_unknown";
+
+// Bucket boundaries recommended for `mcp.server.operation.duration`.
+const DURATION_BOUNDARIES: [f64; 14] = [
+    0.01, 0.02, 0.05, 0.1, 0.2, 0.5, 1.0, 2.0, 5.0, 10.0, 30.0, 60.0, 120.0, 300.0,
+];
+
+/// Metric instruments following the MCP semantic conventions.
+#[derive(Clone)]
+pub struct McpMetrics {
+    operation_duration: Histogram<f64>,
+    tool_calls: Counter<u64>,
+    active_calls: UpDownCounter<i64>,
+    validation_failures: Counter<u64>,
+    channel_reply_timeouts: Counter<u64>,
+    rate_limited: Counter<u64>,
+    active_sessions: UpDownCounter<i64>,
+}
+
+impl McpMetrics {
+    pub fn new(provider: &SdkMeterProvider) -> Self {
+        let meter = provider.meter(METER_NAME);
+        Self {
+            operation_duration: meter
+                .f64_histogram("mcp.server.operation.duration")
+                .with_unit("s")
+                .with_description("Duration of MCP requests handled by the server")
+                .with_boundaries(DURATION_BOUNDARIES.to_vec())
+                .build(),
+            tool_calls: meter
+                .u64_counter("mcp.server.tool.calls")
+                .with_unit("{call}")
+                .with_description("Tool calls handled by the server")
+                .build(),
+            active_calls: meter
+                .i64_up_down_counter("mcp.server.tool.active_calls")
+                .with_unit("{call}")
+                .with_description("Tool calls in flight")
+                .build(),
+            validation_failures: meter
+                .u64_counter("mcp.server.tool.validation_failures")
+                .with_unit("{call}")
+                .with_description("Tool arguments or results rejected by their schema")
+                .build(),
+            channel_reply_timeouts: meter
+                .u64_counter("mcp.server.channel.reply_timeouts")
+                .with_unit("{call}")
+                .with_description("Channel-backed tool calls that timed out awaiting a reply")
+                .build(),
+            rate_limited: meter
+                .u64_counter("mcp.server.tool.rate_limited")
+                .with_unit("{call}")
+                .with_description("Tool calls rejected by a rate limit")
+                .build(),
+            active_sessions: meter
+                .i64_up_down_counter("mcp.server.session.active")
+                .with_unit("{session}")
+                .with_description("MCP sessions currently open")
+                .build(),
+        }
+    }
+
+    /// Record a handled request. `target` is the tool name for `tools/call`.
+    pub fn record_operation(
+        &self,
+        method: &str,
+        target: Option<&str>,
+        error_type: Option<&str>,
+        duration: Duration,
+    ) {
+        let mut attributes = vec![KeyValue::new("mcp.method.name", method.to_string())];
+        if let Some(tool_name) = target {
+            attributes.push(KeyValue::new("gen_ai.tool.name", tool_name.to_string()));
+        }
+        if let Some(error_type) = error_type {
+            attributes.push(KeyValue::new("error.type", error_type.to_string()));
+        }
+        self.operation_duration
+            .record(duration.as_secs_f64(), &attributes);
+        if method == "tools/call" {
+            // Same attributes, without the method, to count calls per tool and error type.
+            self.tool_calls.add(1, &attributes[1..]);
+        }
+    }
+
+    /// Count a session as active until the returned guard is dropped.
+    pub fn session_started(&self) -> ActiveGuard {
+        ActiveGuard::new(&self.active_sessions, Vec::new())
+    }
+
+    /// Count a tool call as in flight until the returned guard is dropped.
+    pub fn call_started(&self, tool_name: &str) -> ActiveGuard {
+        ActiveGuard::new(
+            &self.active_calls,
+            vec![KeyValue::new("gen_ai.tool.name", tool_name.to_string())],
+        )
+    }
+
+    /// Count arguments (`input`) or a result (`output`) that failed schema validation.
+    pub fn record_validation_failure(&self, tool_name: &str, schema: &'static str) {
+        self.validation_failures.add(
+            1,
+            &[
+                KeyValue::new("gen_ai.tool.name", tool_name.to_string()),
+                KeyValue::new("mcp.schema", schema),
+            ],
+        );
+    }
+
+    pub fn record_rate_limited(&self, tool_name: &str) {
+        self.rate_limited.add(
+            1,
+            &[KeyValue::new("gen_ai.tool.name", tool_name.to_string())],
+        );
+    }
+
+    pub fn record_channel_reply_timeout(&self, tool_name: &str, channel: &str) {
+        self.channel_reply_timeouts.add(
+            1,
+            &[
+                KeyValue::new("gen_ai.tool.name", tool_name.to_string()),
+                KeyValue::new("messaging.destination.name", channel.to_string()),
+            ],
+        );
+    }
+}
+
+/// Decrements an up-down counter when dropped, so abandoned work is not left counted.
+pub struct ActiveGuard {
+    counter: UpDownCounter<i64>,
+    attributes: Vec<KeyValue>,
+}
+
+impl ActiveGuard {
+    fn new(counter: &UpDownCounter<i64>, attributes: Vec<KeyValue>) -> Self {
+        counter.add(1, &attributes);
+        Self {
+            counter: counter.clone(),
+            attributes,
+        }
+    }
+}
+
+impl Drop for ActiveGuard {
+    fn drop(&mut self) {
+        self.counter.add(-1, &self.attributes);
+    }
+}
+
+/// Prometheus scrape endpoint backed by the server's meter provider.
+#[derive(Clone)]
+pub struct PrometheusEndpoint {
+    config: PrometheusConfig,
+    registry: Registry,
+}
+
+impl PrometheusEndpoint {
+    pub fn new(config: &PrometheusConfig) -> Self {
+        Self {
+            config: config.clone(),
+            registry: Registry::new(),
+        }
+    }
+
+    pub fn path(&self) -> &str {
+        &self.config.path
+    }
+
+    /// Separate port to serve on, if not alongside the MCP endpoint.
+    pub fn port(&self) -> Option<u16> {
+        self.config.port
+    }
+
+    /// Current metrics in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        TextEncoder::new()
+            .encode_to_string(&self.registry.gather())
+            .unwrap_or_else(|e| {
+                tracing::warn!("Failed to encode Prometheus metrics: {e}");
+                String::new()
+            })
+    }
+
+    /// Router serving the text format at the configured path.
+    pub fn router(&self) -> axum::Router {
+        let endpoint = self.clone();
+        axum::Router::new().route(
+            &self.config.path,
+            axum::routing::get(move || {
+                let body = endpoint.render();
+                std::future::ready((
+                    [(axum::http::header::CONTENT_TYPE, prometheus::TEXT_FORMAT)],
+                    body,
+                ))
+            }),
+        )
+    }
+}
+
+/// Build a meter provider exporting to an OTLP endpoint (`endpoint`, `protocol`),
+/// a Prometheus endpoint, or both. Returns `None` when neither is configured.
+pub fn build_meter_provider(
+    otlp: Option<(&str, &str)>,
+    prometheus: Option<&PrometheusEndpoint>,
+    service_name: &str,
+) -> Result<Option<SdkMeterProvider>> {
+    if otlp.is_none() && prometheus.is_none() {
+        return Ok(None);
+    }
+    let resource = opentelemetry_sdk::Resource::builder()
+        .with_attribute(KeyValue::new("service.name", service_name.to_string()))
+        .build();
+    let mut builder = SdkMeterProvider::builder().with_resource(resource);
+    if let Some((endpoint, protocol)) = otlp {
+        let exporter = match protocol {
+            "http/protobuf" => MetricExporter::builder()
+                .with_http()
+                .with_endpoint(endpoint)
+                .build(),
+            _ => MetricExporter::builder()
+                .with_tonic()
+                .with_endpoint(endpoint)
+                .build(),
+        }
+        .map_err(|e| anyhow::anyhow!("failed to build metric exporter: {e}"))?;
+        builder = builder.with_reader(PeriodicReader::builder(exporter).build());
+    }
+    if let Some(prometheus) = prometheus {
+        let exporter = opentelemetry_prometheus::exporter()
+            .with_registry(prometheus.registry.clone())
+            .build()
+            .map_err(|e| anyhow::anyhow!("failed to build Prometheus exporter: {e}"))?;
+        builder = builder.with_reader(exporter);
+    }
+    Ok(Some(builder.build()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentelemetry_sdk::metrics::InMemoryMetricExporter;
+    use opentelemetry_sdk::metrics::data::{AggregatedMetrics, MetricData};
+
+    // Data points of a sum, with attributes sorted by key.
+    fn sum_of(exporter: &InMemoryMetricExporter, name: &str) -> Vec<(Vec<KeyValue>, i64)> {
+        let mut points = Vec::new();
+        for resource_metrics in exporter.get_finished_metrics().unwrap() {
+            for metric in resource_metrics
+                .scope_metrics()
+                .flat_map(|scope| scope.metrics())
+                .filter(|metric| metric.name() == name)
+            {
+                match metric.data() {
+                    AggregatedMetrics::U64(MetricData::Sum(sum)) => points.extend(
+                        sum.data_points()
+                            .map(|p| (p.attributes().cloned().collect(), p.value() as i64)),
+                    ),
+                    AggregatedMetrics::I64(MetricData::Sum(sum)) => points.extend(
+                        sum.data_points()
+                            .map(|p| (p.attributes().cloned().collect(), p.value())),
+                    ),
+                    _ => {}
+                }
+            }
+        }
+        for (attributes, _) in &mut points {
+            attributes.sort_by(|a, b| a.key.as_str().cmp(b.key.as_str()));
+        }
+        points
+    }
+
+    #[test]
+    fn test_tool_calls_and_sessions() {
+        let exporter = InMemoryMetricExporter::default();
+        let provider = SdkMeterProvider::builder()
+            .with_reader(PeriodicReader::builder(exporter.clone()).build())
+            .build();
+        let metrics = McpMetrics::new(&provider);
+
+        let first = metrics.session_started();
+        let second = metrics.session_started();
+        drop(first);
+        let elapsed = Duration::from_millis(20);
+        metrics.record_operation("tools/call", Some("add"), None, elapsed);
+        metrics.record_operation("tools/call", Some("add"), Some("timeout"), elapsed);
+        metrics.record_operation("tools/list", None, None, elapsed);
+        provider.force_flush().unwrap();
+
+        let mut calls = sum_of(&exporter, "mcp.server.tool.calls");
+        calls.sort_by_key(|(attributes, _)| attributes.len());
+        assert_eq!(
+            calls,
+            [
+                (vec![KeyValue::new("gen_ai.tool.name", "add")], 1),
+                (
+                    vec![
+                        KeyValue::new("error.type", "timeout"),
+                        KeyValue::new("gen_ai.tool.name", "add"),
+                    ],
+                    1
+                ),
+            ]
+        );
+        assert_eq!(
+            sum_of(&exporter, "mcp.server.session.active"),
+            [(Vec::new(), 1)]
+        );
+
+        let durations: usize = exporter
+            .get_finished_metrics()
+            .unwrap()
+            .iter()
+            .flat_map(|rm| rm.scope_metrics())
+            .flat_map(|scope| scope.metrics())
+            .filter(|metric| metric.name() == "mcp.server.operation.duration")
+            .map(|metric| match metric.data() {
+                AggregatedMetrics::F64(MetricData::Histogram(histogram)) => {
+                    histogram.data_points().map(|p| p.count() as usize).sum()
+                }
+                _ => 0,
+            })
+            .sum();
+        assert_eq!(durations, 3);
+        drop(second);
+    }
+
+    #[test]
+    fn test_prometheus_text() {
+        let endpoint = PrometheusEndpoint::new(&PrometheusConfig {
+            path: "/metrics".to_string(),
+            port: None,
+        });
+        let provider = build_meter_provider(None, Some(&endpoint), "mcp")
+            .unwrap()
+            .unwrap();
+        let metrics = McpMetrics::new(&provider);
+
+        let call = metrics.call_started("add");
+        metrics.record_validation_failure("add", "input");
+        metrics.record_channel_reply_timeout("notify", "alerts");
+        metrics.record_rate_limited("add");
+        metrics.record_operation("tools/call", Some("add"), None, Duration::from_millis(5));
+
+        let text = endpoint.render();
+        assert!(text.contains("mcp_server_tool_active_calls"), "{text}");
+        assert!(
+            text.contains("mcp_server_tool_validation_failures_total"),
+            "{text}"
+        );
+        assert!(
+            text.contains("mcp_server_channel_reply_timeouts_total"),
+            "{text}"
+        );
+        assert!(
+            text.contains("mcp_server_operation_duration_seconds_bucket"),
+            "{text}"
+        );
+        assert!(
+            text.contains("mcp_server_tool_rate_limited_total"),
+            "{text}"
+        );
+        assert!(text.contains("gen_ai_tool_name=\"add\""), "{text}");
+        drop(call);
+    }
+}
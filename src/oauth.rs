@@ -0,0 +1,433 @@
+use anyhow::Result;
+use jsonwebtoken::jwk::{AlgorithmParameters, EllipticCurve, Jwk, JwkSet};
+use jsonwebtoken::{Algorithm, DecodingKey, Validation, decode, decode_header};
+use std::time::{Duration, Instant};
+use tokio::sync::{Mutex, RwLock};
+
+use crate::config::{JwksSource, OAuthConfig};
+
+/// Path of the OAuth 2.0 Protected Resource Metadata document (RFC 9728).
+pub const PROTECTED_RESOURCE_METADATA_PATH: &str = "/.well-known/oauth-protected-resource";
+
+// Minimum interval between JWKS refetches triggered by unknown key ids.
+const JWKS_REFRESH_INTERVAL: Duration = Duration::from_secs(300);
+
+// Limit on a JWKS fetch, so a stalled endpoint cannot hang authentication.
+const JWKS_FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Claims extracted from a verified access token.
+#[derive(Debug, Clone)]
+pub struct VerifiedToken {
+    pub subject: String,
+    pub scopes: Vec<String>,
+}
+
+/// Verifies JWT access tokens against a JWKS, checking issuer, audience and expiry.
+pub struct TokenVerifier {
+    config: OAuthConfig,
+    keys: RwLock<JwkSet>,
+    // Time of the last successful fetch. Held across a fetch so concurrent
+    // lookups wait for it instead of failing.
+    last_fetch: Mutex<Option<Instant>>,
+    client: reqwest::Client,
+}
+
+impl TokenVerifier {
+    /// Create a verifier. A JWKS file is read immediately; a JWKS URL is fetched on
+    /// first use and refetched when a token references an unknown key id.
+    /// Requests arriving during a fetch wait for it.
+    pub fn new(server_name: &str, config: &OAuthConfig) -> Result<Self> {
+        let keys = match &config.jwks {
+            JwksSource::File(path) => {
+                let content = std::fs::read_to_string(path).map_err(|e| {
+                    anyhow::anyhow!(
+                        "Server '{server_name}': cannot read JWKS file '{}': {e}",
+                        path.display()
+                    )
+                })?;
+                serde_json::from_str(&content).map_err(|e| {
+                    anyhow::anyhow!(
+                        "Server '{server_name}': invalid JWKS file '{}': {e}",
+                        path.display()
+                    )
+                })?
+            }
+            JwksSource::Url(_) => JwkSet { keys: Vec::new() },
+        };
+        let client = reqwest::Client::builder()
+            .timeout(JWKS_FETCH_TIMEOUT)
+            .build()
+            .map_err(|e| {
+                anyhow::anyhow!("Server '{server_name}': cannot build JWKS client: {e}")
+            })?;
+        Ok(Self {
+            config: config.clone(),
+            keys: RwLock::new(keys),
+            last_fetch: Mutex::new(None),
+            client,
+        })
+    }
+
+    pub fn config(&self) -> &OAuthConfig {
+        &self.config
+    }
+
+    /// Verify the token's signature and its `iss`, `aud` and `exp` claims.
+    pub async fn verify(&self, token: &str) -> std::result::Result<VerifiedToken, String> {
+        let header = decode_header(token).map_err(|e| format!("malformed token: {e}"))?;
+        let jwk = self
+            .key_for(header.kid.as_deref())
+            .await
+            .ok_or_else(|| "no matching key for token".to_string())?;
+        // The header is attacker-controlled, so the key decides which algorithm is acceptable.
+        if !key_algorithms(&jwk).contains(&header.alg) {
+            return Err(format!(
+                "token algorithm {:?} does not match its key",
+                header.alg
+            ));
+        }
+        let key = DecodingKey::from_jwk(&jwk).map_err(|e| format!("unusable key: {e}"))?;
+
+        let mut validation = Validation::new(header.alg);
+        validation.set_issuer(&[&self.config.issuer]);
+        validation.set_audience(&[&self.config.audience]);
+        validation.set_required_spec_claims(&["exp", "iss", "aud", "sub"]);
+
+        let claims = decode::<serde_json::Value>(token, &key, &validation)
+            .map_err(|e| format!("invalid token: {e}"))?
+            .claims;
+
+        let subject = claims
+            .get("sub")
+            .and_then(|s| s.as_str())
+            .unwrap_or_default()
+            .to_string();
+        Ok(VerifiedToken {
+            subject,
+            scopes: token_scopes(&claims),
+        })
+    }
+
+    async fn key_for(&self, kid: Option<&str>) -> Option<Jwk> {
+        if let Some(jwk) = find_key(&*self.keys.read().await, kid) {
+            return Some(jwk);
+        }
+        let JwksSource::Url(url) = &self.config.jwks else {
+            return None;
+        };
+        let mut last_fetch = self.last_fetch.lock().await;
+        // Another request may have fetched the key while this one waited.
+        if let Some(jwk) = find_key(&*self.keys.read().await, kid) {
+            return Some(jwk);
+        }
+        if last_fetch.is_some_and(|t| t.elapsed() < JWKS_REFRESH_INTERVAL) {
+            return None;
+        }
+        match fetch_jwks(&self.client, url).await {
+            Ok(keys) => {
+                *last_fetch = Some(Instant::now());
+                let jwk = find_key(&keys, kid);
+                *self.keys.write().await = keys;
+                jwk
+            }
+            Err(e) => {
+                tracing::warn!(url, "Failed to fetch JWKS: {e}");
+                None
+            }
+        }
+    }
+}
+
+// Find the key with the given id, or the only key when the token has no `kid`.
+fn find_key(keys: &JwkSet, kid: Option<&str>) -> Option<Jwk> {
+    match kid {
+        Some(kid) => keys.find(kid).cloned(),
+        None if keys.keys.len() == 1 => keys.keys.first().cloned(),
+        None => None,
+    }
+}
+
+// Algorithms a key may verify: the one it declares in `alg`, or otherwise
+// the asymmetric algorithms of its key type. Symmetric keys are never accepted.
+fn key_algorithms(jwk: &Jwk) -> Vec<Algorithm> {
+    if let Some(key_algorithm) = &jwk.common.key_algorithm {
+        // Both enums serialize as their JOSE names; encryption algorithms have no match.
+        return serde_json::to_value(key_algorithm)
+            .and_then(serde_json::from_value::<Algorithm>)
+            .into_iter()
+            .filter(|alg| !matches!(alg, Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512))
+            .collect();
+    }
+    match &jwk.algorithm {
+        AlgorithmParameters::EllipticCurve(params) => match params.curve {
+            EllipticCurve::P256 => vec![Algorithm::ES256],
+            EllipticCurve::P384 => vec![Algorithm::ES384],
+            _ => Vec::new(),
+        },
+        AlgorithmParameters::RSA(_) => vec![
+            Algorithm::RS256,
+            Algorithm::RS384,
+            Algorithm::RS512,
+            Algorithm::PS256,
+            Algorithm::PS384,
+            Algorithm::PS512,
+        ],
+        AlgorithmParameters::OctetKeyPair(_) => vec![Algorithm::EdDSA],
+        _ => Vec::new(),
+    }
+}
+
+async fn fetch_jwks(client: &reqwest::Client, url: &str) -> Result<JwkSet> {
+    Ok(client
+        .get(url)
+        .send()
+        .await?
+        .error_for_status()?
+        .json::<JwkSet>()
+        .await?)
+}
+
+// Scopes come from the space-delimited `scope` claim (RFC 9068) or a `scp` array.
+fn token_scopes(claims: &serde_json::Value) -> Vec<String> {
+    if let Some(scope) = claims.get("scope").and_then(|s| s.as_str()) {
+        return scope.split_whitespace().map(|s| s.to_string()).collect();
+    }
+    claims
+        .get("scp")
+        .and_then(|s| s.as_array())
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|s| s.as_str().map(|s| s.to_string()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Build the Protected Resource Metadata document advertised to MCP clients.
+pub fn protected_resource_metadata(
+    config: &OAuthConfig,
+    scopes_supported: &[String],
+) -> serde_json::Value {
+    let mut metadata = serde_json::json!({
+        "resource": config.resource,
+        "authorization_servers": config.authorization_servers,
+        "bearer_methods_supported": ["header"],
+    });
+    if !scopes_supported.is_empty() {
+        metadata["scopes_supported"] = serde_json::json!(scopes_supported);
+    }
+    metadata
+}
+
+/// URL of the metadata document on the same origin as the resource.
+pub fn resource_metadata_url(resource: &str) -> String {
+    let origin = match resource.split_once("://") {
+        Some((scheme, rest)) => {
+            let authority = rest.split('/').next().unwrap_or(rest);
+            format!("{scheme}://{authority}")
+        }
+        None => resource.trim_end_matches('/').to_string(),
+    };
+    format!("{origin}{PROTECTED_RESOURCE_METADATA_PATH}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::Engine as _;
+    use jsonwebtoken::{Algorithm, EncodingKey, Header, encode};
+    use std::io::Write as _;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    // Self-signed P-256 test key and the matching public JWK.
+    const TEST_KEY_PKCS8: &str = "MIGHAgEAMBMGByqGSM49AgEGCCqGSM49AwEHBG0wawIBAQQg3zP+LBL+JXfOF4jO\
+                                  TlAwcmVI9v6sFvC5idNxawiDi/uhRANCAASPFqGz1udmfBgBD2EvMDZEWSreB9ZN\
+                                  EJ5OF8zKvlsgLEYwPSYijybHyPyAMAzq/r9963rBnx8lW/xt2YlYgf0x";
+
+    fn jwks_file() -> tempfile::NamedTempFile {
+        let jwks = serde_json::json!({
+            "keys": [{
+                "kty": "EC",
+                "crv": "P-256",
+                "kid": "test-key",
+                "alg": "ES256",
+                "use": "sig",
+                "x": "jxahs9bnZnwYAQ9hLzA2RFkq3gfWTRCeThfMyr5bICw",
+                "y": "RjA9JiKPJsfI_IAwDOr-v33resGfHyVb_G3ZiViB_TE"
+            }]
+        });
+        let mut f = tempfile::NamedTempFile::new().unwrap();
+        f.write_all(jwks.to_string().as_bytes()).unwrap();
+        f
+    }
+
+    fn verifier(jwks: &tempfile::NamedTempFile) -> TokenVerifier {
+        let config = OAuthConfig {
+            issuer: "https://idp.example.com".to_string(),
+            audience: "https://mcp.example.com/mcp".to_string(),
+            jwks: JwksSource::File(jwks.path().to_path_buf()),
+            resource: "https://mcp.example.com/mcp".to_string(),
+            authorization_servers: vec!["https://idp.example.com".to_string()],
+        };
+        TokenVerifier::new("mcp", &config).unwrap()
+    }
+
+    fn sign(kid: &str, claims: serde_json::Value) -> String {
+        let der = base64::engine::general_purpose::STANDARD
+            .decode(TEST_KEY_PKCS8)
+            .unwrap();
+        let mut header = Header::new(Algorithm::ES256);
+        header.kid = Some(kid.to_string());
+        encode(&header, &claims, &EncodingKey::from_ec_der(&der)).unwrap()
+    }
+
+    fn claims(aud: &str, exp: u64) -> serde_json::Value {
+        serde_json::json!({
+            "sub": "alice",
+            "iss": "https://idp.example.com",
+            "aud": aud,
+            "exp": exp,
+            "scope": "tools:read tools:write"
+        })
+    }
+
+    const FAR_FUTURE: u64 = 4_102_444_800;
+
+    #[tokio::test]
+    async fn test_valid_token() {
+        let jwks = jwks_file();
+        let verifier = verifier(&jwks);
+        let token = sign(
+            "test-key",
+            claims("https://mcp.example.com/mcp", FAR_FUTURE),
+        );
+        let verified = verifier.verify(&token).await.unwrap();
+        assert_eq!(verified.subject, "alice");
+        assert_eq!(verified.scopes, ["tools:read", "tools:write"]);
+    }
+
+    #[tokio::test]
+    async fn test_wrong_audience() {
+        let jwks = jwks_file();
+        let verifier = verifier(&jwks);
+        let token = sign("test-key", claims("https://other.example.com", FAR_FUTURE));
+        assert!(verifier.verify(&token).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_expired_token() {
+        let jwks = jwks_file();
+        let verifier = verifier(&jwks);
+        let token = sign("test-key", claims("https://mcp.example.com/mcp", 1_000_000));
+        let err = verifier.verify(&token).await.unwrap_err();
+        assert!(err.contains("Expired"), "unexpected error: {err}");
+    }
+
+    #[tokio::test]
+    async fn test_unknown_key_id() {
+        let jwks = jwks_file();
+        let verifier = verifier(&jwks);
+        let token = sign("rotated", claims("https://mcp.example.com/mcp", FAR_FUTURE));
+        let err = verifier.verify(&token).await.unwrap_err();
+        assert!(err.contains("no matching key"), "unexpected error: {err}");
+    }
+
+    #[tokio::test]
+    async fn test_algorithm_must_match_key() {
+        let jwks = jwks_file();
+        let verifier = verifier(&jwks);
+        // An HMAC token keyed with public key material must not verify.
+        let mut header = Header::new(Algorithm::HS256);
+        header.kid = Some("test-key".to_string());
+        let token = encode(
+            &header,
+            &claims("https://mcp.example.com/mcp", FAR_FUTURE),
+            &EncodingKey::from_secret(b"jxahs9bnZnwYAQ9hLzA2RFkq3gfWTRCeThfMyr5bICw"),
+        )
+        .unwrap();
+        let err = verifier.verify(&token).await.unwrap_err();
+        assert!(err.contains("does not match"), "unexpected error: {err}");
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_requests_wait_for_jwks_fetch() {
+        let jwks = jwks_file();
+        let body = std::fs::read_to_string(jwks.path()).unwrap();
+        let fetches = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&fetches);
+        let router = axum::Router::new().route(
+            "/jwks.json",
+            axum::routing::get(move || {
+                let body = body.clone();
+                counter.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    // Slow enough that every request arrives mid-fetch.
+                    tokio::time::sleep(Duration::from_millis(200)).await;
+                    body
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+
+        let config = OAuthConfig {
+            issuer: "https://idp.example.com".to_string(),
+            audience: "https://mcp.example.com/mcp".to_string(),
+            jwks: JwksSource::Url(format!("http://{addr}/jwks.json")),
+            resource: "https://mcp.example.com/mcp".to_string(),
+            authorization_servers: vec!["https://idp.example.com".to_string()],
+        };
+        let verifier = TokenVerifier::new("mcp", &config).unwrap();
+        let token = sign(
+            "test-key",
+            claims("https://mcp.example.com/mcp", FAR_FUTURE),
+        );
+        let (a, b, c) = tokio::join!(
+            verifier.verify(&token),
+            verifier.verify(&token),
+            verifier.verify(&token)
+        );
+        for result in [a, b, c] {
+            assert_eq!(result.unwrap().subject, "alice");
+        }
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_key_algorithms() {
+        let mut jwk: Jwk = serde_json::from_value(serde_json::json!({
+            "kty": "EC",
+            "crv": "P-384",
+            "x": "jxahs9bnZnwYAQ9hLzA2RFkq3gfWTRCeThfMyr5bICw",
+            "y": "RjA9JiKPJsfI_IAwDOr-v33resGfHyVb_G3ZiViB_TE"
+        }))
+        .unwrap();
+        assert_eq!(key_algorithms(&jwk), [Algorithm::ES384]);
+        jwk.common.key_algorithm = Some(jsonwebtoken::jwk::KeyAlgorithm::ES256);
+        assert_eq!(key_algorithms(&jwk), [Algorithm::ES256]);
+        jwk.common.key_algorithm = Some(jsonwebtoken::jwk::KeyAlgorithm::HS256);
+        assert!(key_algorithms(&jwk).is_empty());
+    }
+
+    #[test]
+    fn test_scp_array_claim() {
+        let scopes = token_scopes(&serde_json::json!({ "scp": ["a", "b"] }));
+        assert_eq!(scopes, ["a", "b"]);
+        assert!(token_scopes(&serde_json::json!({})).is_empty());
+    }
+
+    #[test]
+    fn test_resource_metadata_url() {
+        assert_eq!(
+            resource_metadata_url("https://mcp.example.com/mcp"),
+            "https://mcp.example.com/.well-known/oauth-protected-resource"
+        );
+        assert_eq!(
+            resource_metadata_url("http://localhost:3001"),
+            "http://localhost:3001/.well-known/oauth-protected-resource"
+        );
+    }
+}
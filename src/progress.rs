@@ -0,0 +1,267 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use composable_runtime::{ComponentState, HostExtension};
+use rmcp::model::{ProgressNotificationParam, ProgressToken};
+use rmcp::{Peer, RoleServer};
+use serde::Deserialize;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use wasmtime::StoreContextMut;
+use wasmtime::component::Linker;
+
+/// Propagation context key carrying the client's `_meta.progressToken`, if any.
+pub const PROGRESS_TOKEN_HEADER: &str = "mcp-progress-token";
+
+/// Propagation context key identifying the session that sent the progress token.
+pub const PROGRESS_SESSION_HEADER: &str = "mcp-progress-session";
+
+/// Header marking a channel reply as a progress report rather than the result.
+pub const PROGRESS_REPLY_HEADER: &str = "mcp-progress";
+
+/// Capability type that offers `composable:mcp/progress` to components.
+pub const PROGRESS_CAPABILITY: &str = "mcp:progress";
+
+const PROGRESS_INTERFACE: &str = "composable:mcp/progress@0.2.0";
+
+// Clients choose their own tokens, so routes are keyed by session as well.
+type Routes = Arc<Mutex<HashMap<(u64, String), mpsc::UnboundedSender<ProgressReport>>>>;
+
+/// Progress of a running tool call, as reported by a component or in an
+/// interim channel reply.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ProgressReport {
+    pub progress: f64,
+    #[serde(default)]
+    pub total: Option<f64>,
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+/// Delivers progress reports to the client whose tool call they belong to.
+///
+/// Reports are addressed by the `mcp-progress-token` and `mcp-progress-session`
+/// entries of the call's propagation context. They are sent as
+/// `notifications/progress` while the call runs, and dropped when no such call
+/// is in flight.
+#[derive(Clone, Default)]
+pub struct ProgressReporter {
+    routes: Routes,
+    // Numbers handed out to sessions. Session 0 is a server serving a single
+    // peer without a per-session copy.
+    sessions: Arc<AtomicU64>,
+    session: u64,
+}
+
+impl ProgressReporter {
+    /// Forward a report for the call with this propagation context.
+    ///
+    /// Returns false if the call has finished or never asked for progress.
+    pub fn report(&self, context: &HashMap<String, String>, report: ProgressReport) -> bool {
+        let Some(key) = route_key(context) else {
+            return false;
+        };
+        self.routes
+            .lock()
+            .unwrap()
+            .get(&key)
+            .is_some_and(|route| route.send(report).is_ok())
+    }
+
+    // A reporter for a new client session, sharing this one's routes.
+    pub(crate) fn for_session(&self) -> Self {
+        Self {
+            session: self.sessions.fetch_add(1, Ordering::Relaxed) + 1,
+            ..self.clone()
+        }
+    }
+
+    // Propagation context entries that address reports to this session's call
+    // with `token`.
+    pub(crate) fn context_entries(&self, token: &ProgressToken) -> [(String, String); 2] {
+        [
+            (PROGRESS_TOKEN_HEADER.to_string(), token.0.to_string()),
+            (
+                PROGRESS_SESSION_HEADER.to_string(),
+                self.session.to_string(),
+            ),
+        ]
+    }
+
+    // Send reports for `token` to `peer` until the route is closed.
+    pub(crate) fn open(&self, token: ProgressToken, peer: Peer<RoleServer>) -> ProgressRoute {
+        let (mut route, mut reports) = self.register(token.0.to_string());
+        route.forwarder = Some(tokio::spawn(async move {
+            while let Some(report) = reports.recv().await {
+                let notification = ProgressNotificationParam {
+                    progress_token: token.clone(),
+                    progress: report.progress,
+                    total: report.total,
+                    message: report.message,
+                };
+                if let Err(e) = peer.notify_progress(notification).await {
+                    tracing::debug!("Failed to send progress notification: {e}");
+                    return;
+                }
+            }
+        }));
+        route
+    }
+
+    fn register(&self, token: String) -> (ProgressRoute, mpsc::UnboundedReceiver<ProgressReport>) {
+        let key = (self.session, token);
+        let (sender, reports) = mpsc::unbounded_channel();
+        self.routes
+            .lock()
+            .unwrap()
+            .insert(key.clone(), sender.clone());
+        let route = ProgressRoute {
+            key,
+            sender: Some(sender),
+            routes: Arc::clone(&self.routes),
+            forwarder: None,
+        };
+        (route, reports)
+    }
+}
+
+// Session and token a propagation context addresses reports to.
+fn route_key(context: &HashMap<String, String>) -> Option<(u64, String)> {
+    let session = context.get(PROGRESS_SESSION_HEADER)?.parse().ok()?;
+    let token = context.get(PROGRESS_TOKEN_HEADER)?;
+    Some((session, token.clone()))
+}
+
+/// Binds the `composable:mcp/progress` import of components to the reporter.
+///
+/// The report is addressed by the propagation context of the invocation that
+/// made it, so components never see the route.
+impl HostExtension for ProgressReporter {
+    fn interfaces(&self) -> Vec<String> {
+        vec![PROGRESS_INTERFACE.to_string()]
+    }
+
+    fn link(&self, linker: &mut Linker<ComponentState>) -> anyhow::Result<()> {
+        let reporter = self.clone();
+        linker.instance(PROGRESS_INTERFACE)?.func_wrap(
+            "report",
+            move |store: StoreContextMut<'_, ComponentState>,
+                  (progress, total, message): (f64, Option<f64>, Option<String>)| {
+                let report = ProgressReport {
+                    progress,
+                    total,
+                    message,
+                };
+                reporter.report(store.data().propagation_context(), report);
+                Ok(())
+            },
+        )?;
+        Ok(())
+    }
+}
+
+/// An open progress route for one tool call. Dropping it stops accepting reports.
+pub(crate) struct ProgressRoute {
+    key: (u64, String),
+    // Identifies this route's entry, which a later call may have replaced.
+    sender: Option<mpsc::UnboundedSender<ProgressReport>>,
+    routes: Routes,
+    forwarder: Option<JoinHandle<()>>,
+}
+
+impl ProgressRoute {
+    /// Stop accepting reports and wait until those already made are sent, so
+    /// that none reach the client after the call's result.
+    pub(crate) async fn close(mut self) {
+        self.remove();
+        if let Some(forwarder) = self.forwarder.take() {
+            let _ = forwarder.await;
+        }
+    }
+
+    fn remove(&mut self) {
+        let Some(sender) = self.sender.take() else {
+            return;
+        };
+        let mut routes = self.routes.lock().unwrap();
+        if routes
+            .get(&self.key)
+            .is_some_and(|route| route.same_channel(&sender))
+        {
+            routes.remove(&self.key);
+        }
+    }
+}
+
+impl Drop for ProgressRoute {
+    fn drop(&mut self) {
+        self.remove();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::model::NumberOrString;
+
+    fn report(progress: f64) -> ProgressReport {
+        ProgressReport {
+            progress,
+            total: None,
+            message: None,
+        }
+    }
+
+    #[test]
+    fn test_routes_are_per_session() {
+        let reporter = ProgressReporter::default();
+        let (first, second) = (reporter.for_session(), reporter.for_session());
+        // Both clients chose the same token.
+        let token = ProgressToken(NumberOrString::Number(1));
+        let first_context = HashMap::from(first.context_entries(&token));
+        let second_context = HashMap::from(second.context_entries(&token));
+        let (first_route, mut first_reports) = first.register(token.0.to_string());
+        let (second_route, mut second_reports) = second.register(token.0.to_string());
+
+        assert!(reporter.report(&first_context, report(1.0)));
+        assert!(reporter.report(&second_context, report(2.0)));
+        assert_eq!(first_reports.try_recv().unwrap(), report(1.0));
+        assert_eq!(second_reports.try_recv().unwrap(), report(2.0));
+        assert!(first_reports.try_recv().is_err());
+
+        // Ending one call leaves the other's route in place.
+        drop(first_route);
+        assert!(!reporter.report(&first_context, report(3.0)));
+        assert!(reporter.report(&second_context, report(3.0)));
+        drop(second_route);
+        assert!(!reporter.report(&second_context, report(4.0)));
+
+        // Without a progress token, nothing is addressed.
+        assert!(!reporter.report(&HashMap::new(), report(5.0)));
+    }
+
+    #[test]
+    fn test_replaced_route_survives_old_route() {
+        let reporter = ProgressReporter::default();
+        let token = ProgressToken(NumberOrString::String("crawl".into()));
+        let context = HashMap::from(reporter.context_entries(&token));
+        let (old_route, _) = reporter.register(token.0.to_string());
+        let (_new_route, mut reports) = reporter.register(token.0.to_string());
+
+        drop(old_route);
+        assert!(reporter.report(&context, report(1.0)));
+        assert_eq!(reports.try_recv().unwrap(), report(1.0));
+    }
+
+    #[test]
+    fn test_progress_reply_body() {
+        let report: ProgressReport =
+            serde_json::from_str(r#"{"progress": 3, "total": 10, "message": "page 3"}"#).unwrap();
+        assert_eq!(report.progress, 3.0);
+        assert_eq!(report.total, Some(10.0));
+        assert_eq!(report.message.as_deref(), Some("page 3"));
+        let report: ProgressReport = serde_json::from_str(r#"{"progress": 0.5}"#).unwrap();
+        assert_eq!(report.total, None);
+    }
+}
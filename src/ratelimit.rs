@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::config::RateLimitConfig;
+
+// Number of buckets above which refilled ones are dropped, so that ended
+// sessions do not accumulate. After each pruning the next one waits until the
+// remaining buckets have doubled, so its cost is spread over the insertions.
+const PRUNE_THRESHOLD: usize = 1024;
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+    limit: RateLimitConfig,
+}
+
+impl Bucket {
+    fn refill_per_sec(limit: &RateLimitConfig) -> f64 {
+        f64::from(limit.requests) / limit.per.as_secs_f64()
+    }
+
+    // Tokens available at `now`, refilled since the last update up to the burst size.
+    fn tokens_at(&self, now: Instant) -> f64 {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        (self.tokens + elapsed * Self::refill_per_sec(&self.limit)).min(f64::from(self.limit.burst))
+    }
+}
+
+#[derive(Default)]
+struct Buckets {
+    by_key: HashMap<(String, String), Bucket>,
+    // Size at which the next new bucket triggers pruning.
+    prune_at: usize,
+}
+
+/// Token buckets for tool calls, one per tool and session.
+///
+/// There is deliberately no limit across a session's tools: each tool's limit
+/// reflects what its backend can sustain, and a session calling several tools
+/// spreads its load over them.
+#[derive(Default)]
+pub struct RateLimiter {
+    buckets: Mutex<Buckets>,
+}
+
+impl RateLimiter {
+    /// Take a token for a call to `tool_name` in `session_id`.
+    ///
+    /// Returns how long to wait before retrying if the bucket is empty.
+    pub fn acquire(
+        &self,
+        tool_name: &str,
+        session_id: &str,
+        limit: &RateLimitConfig,
+        now: Instant,
+    ) -> Result<(), Duration> {
+        let mut buckets = self.buckets.lock().unwrap();
+        let key = (tool_name.to_string(), session_id.to_string());
+        if !buckets.by_key.contains_key(&key) && buckets.by_key.len() >= buckets.prune_at {
+            buckets.prune(now);
+        }
+        let bucket = buckets.by_key.entry(key).or_insert_with(|| Bucket {
+            tokens: f64::from(limit.burst),
+            updated: now,
+            limit: *limit,
+        });
+        // A reloaded tool may have a new limit; keep the tokens already earned.
+        bucket.tokens = bucket.tokens_at(now);
+        bucket.updated = now;
+        if bucket.limit != *limit {
+            bucket.limit = *limit;
+            bucket.tokens = bucket.tokens.min(f64::from(limit.burst));
+        }
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            return Ok(());
+        }
+        let wait_secs = (1.0 - bucket.tokens) / Bucket::refill_per_sec(limit);
+        // Rounded up to whole milliseconds so a retry at that time succeeds.
+        Err(Duration::from_millis((wait_secs * 1000.0).ceil() as u64))
+    }
+}
+
+impl Buckets {
+    // Drop buckets that have refilled completely; a new bucket starts full.
+    fn prune(&mut self, now: Instant) {
+        self.by_key
+            .retain(|_, bucket| bucket.tokens_at(now) < f64::from(bucket.limit.burst));
+        self.prune_at = PRUNE_THRESHOLD.max(2 * self.by_key.len());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bucket_per_tool_and_session() {
+        let limiter = RateLimiter::default();
+        let limit = RateLimitConfig {
+            requests: 2,
+            per: Duration::from_secs(1),
+            burst: 3,
+        };
+        let start = Instant::now();
+
+        for _ in 0..3 {
+            assert_eq!(limiter.acquire("report", "a", &limit, start), Ok(()));
+        }
+        assert_eq!(
+            limiter.acquire("report", "a", &limit, start),
+            Err(Duration::from_millis(500))
+        );
+        // Other sessions and tools have their own buckets.
+        assert_eq!(limiter.acquire("report", "b", &limit, start), Ok(()));
+        assert_eq!(limiter.acquire("add", "a", &limit, start), Ok(()));
+
+        let later = start + Duration::from_millis(500);
+        assert_eq!(limiter.acquire("report", "a", &limit, later), Ok(()));
+        assert_eq!(
+            limiter.acquire("report", "a", &limit, later),
+            Err(Duration::from_millis(500))
+        );
+        // Idle time refills up to the burst size, not beyond.
+        let idle = start + Duration::from_secs(60);
+        for _ in 0..3 {
+            assert_eq!(limiter.acquire("report", "a", &limit, idle), Ok(()));
+        }
+        assert!(limiter.acquire("report", "a", &limit, idle).is_err());
+    }
+
+    #[test]
+    fn test_pruning_is_amortized() {
+        let limiter = RateLimiter::default();
+        let limit = RateLimitConfig {
+            requests: 1,
+            per: Duration::from_secs(60),
+            burst: 1,
+        };
+        let start = Instant::now();
+        let sessions = |range: std::ops::Range<usize>, now| {
+            for session in range {
+                let _ = limiter.acquire("report", &session.to_string(), &limit, now);
+            }
+        };
+        let len = || limiter.buckets.lock().unwrap().by_key.len();
+
+        // None of these buckets have refilled, so pruning keeps them all and
+        // waits for twice as many before trying again.
+        sessions(0..PRUNE_THRESHOLD + 1, start);
+        assert_eq!(len(), PRUNE_THRESHOLD + 1);
+        assert_eq!(
+            limiter.buckets.lock().unwrap().prune_at,
+            2 * PRUNE_THRESHOLD
+        );
+
+        // Refilled buckets are only dropped once that size is reached.
+        let idle = start + Duration::from_secs(60);
+        sessions(PRUNE_THRESHOLD + 1..2 * PRUNE_THRESHOLD, idle);
+        assert_eq!(len(), 2 * PRUNE_THRESHOLD);
+        let later = idle + Duration::from_secs(60);
+        sessions(2 * PRUNE_THRESHOLD..2 * PRUNE_THRESHOLD + 1, later);
+        assert_eq!(len(), 1);
+    }
+}
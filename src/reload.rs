@@ -0,0 +1,221 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::sync::watch;
+
+use composable_runtime::ComponentInvoker;
+
+use crate::config;
+use crate::server::ToolsHandle;
+use crate::service::{McpService, resolve_tools};
+
+// How often definition files are checked for changes.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Poll the definition files and replace each running server's tools when they change.
+///
+/// Only tool definitions are reloaded. Changes to components, listeners or auth
+/// still require a restart. If the new definitions fail to load or resolve, the
+/// running tools are kept and the error is logged.
+pub async fn watch_definitions(
+    paths: Vec<PathBuf>,
+    servers: Vec<(String, ToolsHandle)>,
+    invoker: Arc<dyn ComponentInvoker>,
+    mut shutdown: watch::Receiver<bool>,
+) {
+    let mut last_modified = modified_times(&paths);
+    let mut interval = tokio::time::interval(POLL_INTERVAL);
+    tracing::info!("Watching {} definition files for changes", paths.len());
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {}
+            _ = shutdown.changed() => return,
+        }
+        let modified = modified_times(&paths);
+        if modified == last_modified {
+            continue;
+        }
+        last_modified = modified;
+
+        let mut configs = match McpService::load_definitions(&paths).await {
+            Ok((_, configs)) => configs,
+            Err(e) => {
+                tracing::error!("Definition reload failed, keeping current tools: {e}");
+                continue;
+            }
+        };
+        if configs.is_empty() {
+            configs.push(config::default_server());
+        }
+
+        for (name, handle) in &servers {
+            let Some(server_config) = configs.iter().find(|c| &c.name == name) else {
+                tracing::warn!(
+                    server_name = name,
+                    "Server removed from definitions; restart to stop it"
+                );
+                continue;
+            };
+            match resolve_tools(server_config, &*invoker) {
+                Ok(tools) => {
+                    let tool_count = tools.len();
+                    if handle.replace(tools).await {
+                        tracing::info!(
+                            server_name = name,
+                            "Reloaded {tool_count} {}",
+                            if tool_count == 1 { "tool" } else { "tools" },
+                        );
+                    }
+                }
+                Err(e) => {
+                    tracing::error!(
+                        server_name = name,
+                        "Tool reload failed, keeping current tools: {e}"
+                    );
+                }
+            }
+        }
+        for server_config in &configs {
+            if !servers.iter().any(|(name, _)| name == &server_config.name) {
+                tracing::warn!(
+                    server_name = server_config.name,
+                    "New server in definitions; restart to start it"
+                );
+            }
+        }
+    }
+}
+
+fn is_definition_file(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "toml")
+}
+
+fn modified_times(paths: &[PathBuf]) -> HashMap<PathBuf, Option<SystemTime>> {
+    paths
+        .iter()
+        .filter(|p| is_definition_file(p))
+        .map(|p| {
+            let modified = std::fs::metadata(p).and_then(|m| m.modified()).ok();
+            (p.clone(), modified)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::origin::OriginPolicy;
+    use crate::server::McpServer;
+    use rmcp::model::ClientInfo;
+    use rmcp::service::NotificationContext;
+    use rmcp::{ClientHandler, RoleClient, ServiceExt};
+    use std::io::Write as _;
+    use tokio::sync::mpsc;
+
+    const QUEUE_TOOL: &str = r#"
+        [server.mcp]
+        type = "mcp"
+        port = 3001
+
+        [server.mcp.tool.enqueue]
+        channel = "work-queue"
+        input-schema = { type = "object" }
+        "#;
+
+    // Reports each tools/list_changed notification.
+    struct ListChangedHandler(mpsc::UnboundedSender<()>);
+
+    impl ClientHandler for ListChangedHandler {
+        fn get_info(&self) -> ClientInfo {
+            ClientInfo::default()
+        }
+
+        async fn on_tool_list_changed(&self, _context: NotificationContext<RoleClient>) {
+            let _ = self.0.send(());
+        }
+    }
+
+    fn rewrite(path: &Path, content: &str) {
+        std::fs::write(path, content).unwrap();
+        // Make the change visible regardless of the file system's timestamp
+        // resolution.
+        let file = std::fs::File::options().write(true).open(path).unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(60))
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_reload_notifies_connected_client() {
+        let mut file = tempfile::Builder::new().suffix(".toml").tempfile().unwrap();
+        file.write_all(QUEUE_TOOL.as_bytes()).unwrap();
+        let paths = vec![file.path().to_path_buf()];
+
+        let (runtime, configs) = McpService::load_definitions(&paths).await.unwrap();
+        let invoker = runtime.invoker();
+        let tools = resolve_tools(&configs[0], &*invoker).unwrap();
+        let server = McpServer::new(
+            tools,
+            Arc::clone(&invoker),
+            None,
+            "127.0.0.1:0".parse().unwrap(),
+            OriginPolicy::AllowAll,
+            None,
+        )
+        .with_tools_list_changed(true);
+        let handle = server.tools_handle();
+
+        let (server_transport, client_transport) = tokio::io::duplex(4096);
+        let server_task = tokio::spawn(async move {
+            let running = server.serve(server_transport).await?;
+            running.waiting().await?;
+            anyhow::Ok(())
+        });
+        let (notified, mut notifications) = mpsc::unbounded_channel();
+        let client = ListChangedHandler(notified)
+            .serve(client_transport)
+            .await
+            .unwrap();
+        let names = |tools: Vec<rmcp::model::Tool>| -> Vec<String> {
+            tools.into_iter().map(|t| t.name.to_string()).collect()
+        };
+        assert_eq!(
+            names(client.list_tools(None).await.unwrap().tools),
+            ["enqueue"]
+        );
+
+        let (shutdown_tx, shutdown_rx) = watch::channel(false);
+        let watcher = tokio::spawn(watch_definitions(
+            paths,
+            vec![("mcp".to_string(), handle)],
+            invoker,
+            shutdown_rx,
+        ));
+        // Let the watcher record the current modification time first.
+        tokio::task::yield_now().await;
+
+        rewrite(
+            file.path(),
+            &format!(
+                "{QUEUE_TOOL}
+                [server.mcp.tool.dequeue]
+                channel = \"work-queue\"
+                input-schema = {{ type = \"object\" }}
+                "
+            ),
+        );
+        tokio::time::timeout(Duration::from_secs(10), notifications.recv())
+            .await
+            .expect("no tools/list_changed notification")
+            .unwrap();
+        assert_eq!(
+            names(client.list_tools(None).await.unwrap().tools),
+            ["dequeue", "enqueue"]
+        );
+
+        shutdown_tx.send(true).unwrap();
+        watcher.await.unwrap();
+        client.cancel().await.unwrap();
+        server_task.abort();
+    }
+}
@@ -0,0 +1,146 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+
+/// A URI template with simple `{name}` expressions (RFC 6570 level 1).
+///
+/// Templates without expressions describe a single concrete resource.
+#[derive(Debug, Clone)]
+pub struct UriTemplate {
+    template: String,
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Literal(String),
+    Variable(String),
+}
+
+impl UriTemplate {
+    pub fn parse(template: &str) -> Result<Self> {
+        let mut segments = Vec::new();
+        let mut rest = template;
+        while let Some(start) = rest.find('{') {
+            if start > 0 {
+                segments.push(Segment::Literal(rest[..start].to_string()));
+            }
+            let end = rest[start..].find('}').ok_or_else(|| {
+                anyhow::anyhow!("unterminated expression in URI template '{template}'")
+            })? + start;
+            let name = &rest[start + 1..end];
+            if name.is_empty()
+                || !name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+            {
+                return Err(anyhow::anyhow!(
+                    "invalid variable '{name}' in URI template '{template}'"
+                ));
+            }
+            if matches!(segments.last(), Some(Segment::Variable(_))) {
+                return Err(anyhow::anyhow!(
+                    "adjacent variables in URI template '{template}' must be separated by a literal"
+                ));
+            }
+            segments.push(Segment::Variable(name.to_string()));
+            rest = &rest[end + 1..];
+        }
+        if rest.contains('}') {
+            return Err(anyhow::anyhow!(
+                "unmatched '}}' in URI template '{template}'"
+            ));
+        }
+        if !rest.is_empty() {
+            segments.push(Segment::Literal(rest.to_string()));
+        }
+        Ok(Self {
+            template: template.to_string(),
+            segments,
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.template
+    }
+
+    /// True if the template contains no variables, i.e. it is a concrete URI.
+    pub fn is_concrete(&self) -> bool {
+        self.variables().next().is_none()
+    }
+
+    pub fn variables(&self) -> impl Iterator<Item = &str> {
+        self.segments.iter().filter_map(|s| match s {
+            Segment::Variable(name) => Some(name.as_str()),
+            Segment::Literal(_) => None,
+        })
+    }
+
+    /// Match a URI against the template, returning the variable bindings.
+    ///
+    /// Variables match one or more characters up to the next literal and never span a `/`.
+    pub fn match_uri(&self, uri: &str) -> Option<HashMap<String, String>> {
+        let mut bindings = HashMap::new();
+        let mut rest = uri;
+        let mut segments = self.segments.iter().peekable();
+        while let Some(segment) = segments.next() {
+            match segment {
+                Segment::Literal(literal) => {
+                    rest = rest.strip_prefix(literal.as_str())?;
+                }
+                Segment::Variable(name) => {
+                    let end = match segments.peek() {
+                        Some(Segment::Literal(next)) => rest.find(next.as_str())?,
+                        _ => rest.len(),
+                    };
+                    let value = &rest[..end];
+                    if value.is_empty() || value.contains('/') {
+                        return None;
+                    }
+                    bindings.insert(name.clone(), value.to_string());
+                    rest = &rest[end..];
+                }
+            }
+        }
+        rest.is_empty().then_some(bindings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn concrete_uri_matches_exactly() {
+        let template = UriTemplate::parse("docs://readme").unwrap();
+        assert!(template.is_concrete());
+        assert_eq!(template.match_uri("docs://readme"), Some(HashMap::new()));
+        assert!(template.match_uri("docs://readme2").is_none());
+    }
+
+    #[test]
+    fn variables_are_bound() {
+        let template = UriTemplate::parse("db://{table}/rows/{id}").unwrap();
+        assert!(!template.is_concrete());
+        assert_eq!(template.variables().collect::<Vec<_>>(), ["table", "id"]);
+
+        let bindings = template.match_uri("db://users/rows/42").unwrap();
+        assert_eq!(bindings["table"], "users");
+        assert_eq!(bindings["id"], "42");
+    }
+
+    #[test]
+    fn variables_do_not_span_segments() {
+        let template = UriTemplate::parse("files://{name}").unwrap();
+        assert!(template.match_uri("files://a/b").is_none());
+        assert!(template.match_uri("files://").is_none());
+    }
+
+    #[test]
+    fn invalid_templates_rejected() {
+        assert!(UriTemplate::parse("db://{table").is_err());
+        assert!(UriTemplate::parse("db://{}").is_err());
+        assert!(UriTemplate::parse("db://{a}{b}").is_err());
+        assert!(UriTemplate::parse("db://a}").is_err());
+    }
+}
@@ -1,43 +1,189 @@
 use anyhow::Result;
+use base64::Engine as _;
 use opentelemetry::KeyValue;
 use opentelemetry::propagation::TextMapPropagator;
 use opentelemetry::trace::{Span, SpanKind, Status, Tracer, TracerProvider as _};
 use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+use opentelemetry_sdk::metrics::SdkMeterProvider;
 use opentelemetry_sdk::propagation::TraceContextPropagator;
 use opentelemetry_sdk::trace::{BatchSpanProcessor, SdkTracerProvider};
 use rmcp::{
-    ServerHandler,
+    ServerHandler, ServiceExt,
     model::{
-        CallToolRequestParams, CallToolResult, Content, InitializeRequestParams, InitializeResult,
-        JsonObject, ListToolsResult, Meta, PaginatedRequestParams, ServerCapabilities, ServerInfo,
+        AnnotateAble, CallToolRequestParams, CallToolResult, Content, GetPromptRequestParams,
+        GetPromptResult, InitializeRequestParams, InitializeResult, JsonObject, ListPromptsResult,
+        ListResourceTemplatesResult, ListResourcesResult, ListToolsResult, Meta,
+        PaginatedRequestParams, PromptMessage, PromptMessageRole, PromptsCapability,
+        RawAudioContent, RawContent, RawResource, RawResourceTemplate, ReadResourceRequestParams,
+        ReadResourceResult, ResourceContents, ResourcesCapability, ServerCapabilities, ServerInfo,
         Tool,
     },
-    service::{RequestContext, RoleServer},
+    service::{Peer, RequestContext, RoleServer},
     transport::StreamableHttpService,
     transport::streamable_http_server::session::local::LocalSessionManager,
 };
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{DefaultHasher, Hash, Hasher};
 use std::net::SocketAddr;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
 use tokio::sync::watch;
-
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+
+use crate::audit::{AuditEvent, AuditLog, Caller};
+use crate::auth::{AuthPolicy, Principal, authenticate};
+use crate::config::{HealthConfig, Transport};
+use crate::mapper::McpMapper;
+use crate::metrics::{ActiveGuard, McpMetrics, PrometheusEndpoint, UNKNOWN_TOOL};
+use crate::oauth::{PROTECTED_RESOURCE_METADATA_PATH, protected_resource_metadata};
 use crate::origin::{OriginPolicy, validate_origin};
-use crate::service::{ResolvedTool, ResolvedToolTarget};
-use composable_runtime::{ComponentInvoker, Function, MessagePublisher, PROPAGATED_HEADERS};
+use crate::progress::{PROGRESS_REPLY_HEADER, ProgressReport, ProgressReporter};
+use crate::ratelimit::RateLimiter;
+use crate::service::{ResolvedPrompt, ResolvedResource, ResolvedTool, ResolvedToolTarget};
+use composable_runtime::{
+    ComponentInvoker, Function, MessagePublisher, PROPAGATED_HEADERS, ResultError,
+};
+
+/// An immutable set of tools, replaced as a whole when definitions are reloaded.
+struct ToolSet {
+    // Ordered by name so that tools/list pages are stable.
+    tools: BTreeMap<String, ResolvedTool>,
+    // Identifies the tool set that pagination cursors were issued against.
+    snapshot: u64,
+}
+
+impl ToolSet {
+    fn new(tools: BTreeMap<String, ResolvedTool>) -> Self {
+        Self {
+            snapshot: tools_snapshot(&tools),
+            tools,
+        }
+    }
+}
+
+/// Shared handle for replacing a running server's tools.
+#[derive(Clone)]
+pub struct ToolsHandle {
+    current: Arc<RwLock<Arc<ToolSet>>>,
+    // Sessions to notify when the tool set changes.
+    peers: Arc<Mutex<Vec<Peer<RoleServer>>>>,
+}
+
+impl ToolsHandle {
+    fn new(tools: BTreeMap<String, ResolvedTool>) -> Self {
+        Self {
+            current: Arc::new(RwLock::new(Arc::new(ToolSet::new(tools)))),
+            peers: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    fn current(&self) -> Arc<ToolSet> {
+        Arc::clone(&self.current.read().unwrap())
+    }
+
+    // Swap in a new tool set. Returns false if it is identical to the current one.
+    fn swap(&self, tools: BTreeMap<String, ResolvedTool>) -> bool {
+        let next = ToolSet::new(tools);
+        let mut current = self.current.write().unwrap();
+        if current.snapshot == next.snapshot {
+            return false;
+        }
+        *current = Arc::new(next);
+        true
+    }
+
+    /// Atomically replace the tools and send `notifications/tools/list_changed`
+    /// to connected sessions if they differ from the current tools.
+    pub async fn replace(&self, tools: BTreeMap<String, ResolvedTool>) -> bool {
+        if !self.swap(tools) {
+            return false;
+        }
+        let peers = std::mem::take(&mut *self.peers.lock().unwrap());
+        let mut connected = Vec::with_capacity(peers.len());
+        for peer in peers {
+            // Sessions that can no longer be notified have gone away.
+            if peer.notify_tool_list_changed().await.is_ok() {
+                connected.push(peer);
+            }
+        }
+        self.peers.lock().unwrap().extend(connected);
+        true
+    }
+
+    fn register(&self, peer: Peer<RoleServer>) {
+        let mut peers = self.peers.lock().unwrap();
+        // Also drop sessions that ended since, for servers that never reload.
+        peers.retain(|peer| !peer.is_transport_closed());
+        peers.push(peer);
+    }
+
+    // Union of the scopes required by any tool, advertised in resource metadata.
+    fn scopes_supported(&self) -> Vec<String> {
+        let tool_set = self.current();
+        let scopes: std::collections::BTreeSet<&String> = tool_set
+            .tools
+            .values()
+            .flat_map(|r| &r.required_scopes)
+            .collect();
+        scopes.into_iter().cloned().collect()
+    }
+
+    // Scopes required by any of the called tools whose requirements are not
+    // all granted. Unknown tools need none; they fail as usual in `tools/call`.
+    fn missing_scopes(&self, tool_names: &[String], granted: &[String]) -> Vec<String> {
+        let tool_set = self.current();
+        let mut scopes = Vec::new();
+        for resolved in tool_names
+            .iter()
+            .filter_map(|name| tool_set.tools.get(name))
+        {
+            if resolved
+                .required_scopes
+                .iter()
+                .any(|s| !granted.contains(s))
+            {
+                for scope in &resolved.required_scopes {
+                    if !scopes.contains(scope) {
+                        scopes.push(scope.clone());
+                    }
+                }
+            }
+        }
+        scopes
+    }
+}
 
 #[derive(Clone)]
 pub struct McpServer {
-    tools: HashMap<String, ResolvedTool>,
+    name: String,
+    tools: ToolsHandle,
+    // Whether tools/list_changed notifications are sent on reload.
+    tools_list_changed: bool,
+    page_size: Option<usize>,
+    resources: Vec<ResolvedResource>,
+    prompts: Vec<ResolvedPrompt>,
     invoker: Arc<dyn ComponentInvoker>,
     publisher: Option<Arc<dyn MessagePublisher>>,
+    transport: Transport,
     addr: SocketAddr,
     origin_policy: OriginPolicy,
+    auth_policy: Option<AuthPolicy>,
+    health: HealthConfig,
+    audit: Option<AuditLog>,
+    rate_limiter: Arc<RateLimiter>,
+    progress: ProgressReporter,
     tracer_provider: Option<Arc<SdkTracerProvider>>,
+    meter_provider: Option<Arc<SdkMeterProvider>>,
+    metrics: Option<McpMetrics>,
+    prometheus: Option<PrometheusEndpoint>,
+    // Held by each session's copy of the server; dropped when the session ends.
+    session: Option<Arc<ActiveGuard>>,
 }
 
 impl McpServer {
     pub fn new(
-        tools: HashMap<String, ResolvedTool>,
+        tools: BTreeMap<String, ResolvedTool>,
         invoker: Arc<dyn ComponentInvoker>,
         publisher: Option<Arc<dyn MessagePublisher>>,
         addr: SocketAddr,
@@ -45,31 +191,343 @@ impl McpServer {
         tracer_provider: Option<SdkTracerProvider>,
     ) -> Self {
         Self {
-            tools,
+            name: "mcp".to_string(),
+            tools: ToolsHandle::new(tools),
+            tools_list_changed: false,
+            page_size: None,
+            resources: Vec::new(),
+            prompts: Vec::new(),
             invoker,
             publisher,
+            transport: Transport::StreamableHttp,
             addr,
             origin_policy,
+            auth_policy: None,
+            health: HealthConfig::default(),
+            audit: None,
+            rate_limiter: Arc::default(),
+            progress: ProgressReporter::default(),
             tracer_provider: tracer_provider.map(Arc::new),
+            meter_provider: None,
+            metrics: None,
+            prometheus: None,
+            session: None,
+        }
+    }
+
+    /// Record request metrics with this provider.
+    pub fn with_metrics(mut self, meter_provider: Option<SdkMeterProvider>) -> Self {
+        self.metrics = meter_provider.as_ref().map(McpMetrics::new);
+        self.meter_provider = meter_provider.map(Arc::new);
+        self
+    }
+
+    /// Name reported by the readiness probe. Defaults to "mcp".
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = name.to_string();
+        self
+    }
+
+    /// Paths of the liveness and readiness probes on the HTTP endpoint.
+    pub fn with_health(mut self, health: HealthConfig) -> Self {
+        self.health = health;
+        self
+    }
+
+    /// Write an audit record for every `tools/call`.
+    pub fn with_audit(mut self, audit: Option<AuditLog>) -> Self {
+        self.audit = audit;
+        self
+    }
+
+    /// Serve the meter provider's Prometheus registry for scraping.
+    pub fn with_prometheus(mut self, prometheus: Option<PrometheusEndpoint>) -> Self {
+        self.prometheus = prometheus;
+        self
+    }
+
+    /// Select the transport. Defaults to Streamable HTTP.
+    pub fn with_transport(mut self, transport: Transport) -> Self {
+        self.transport = transport;
+        self
+    }
+
+    /// Advertise `listChanged` for tools, for servers whose tools may be reloaded.
+    pub fn with_tools_list_changed(mut self, enabled: bool) -> Self {
+        self.tools_list_changed = enabled;
+        self
+    }
+
+    /// Forward progress reported by components to the calling client.
+    pub fn with_progress(mut self, progress: ProgressReporter) -> Self {
+        self.progress = progress;
+        self
+    }
+
+    /// Handle for replacing this server's tools while it runs.
+    pub fn tools_handle(&self) -> ToolsHandle {
+        self.tools.clone()
+    }
+
+    /// Require credentials on the HTTP endpoint.
+    pub fn with_auth(mut self, auth_policy: Option<AuthPolicy>) -> Self {
+        self.auth_policy = auth_policy;
+        self
+    }
+
+    /// Limit the number of tools returned per tools/list page. `None` returns all tools.
+    pub fn with_page_size(mut self, page_size: Option<usize>) -> Self {
+        self.page_size = page_size;
+        self
+    }
+
+    /// Serve component-backed resources alongside tools.
+    pub fn with_resources(mut self, resources: Vec<ResolvedResource>) -> Self {
+        self.resources = resources;
+        self
+    }
+
+    /// Serve component-backed prompts alongside tools.
+    pub fn with_prompts(mut self, prompts: Vec<ResolvedPrompt>) -> Self {
+        self.prompts = prompts;
+        self
+    }
+
+    // A copy of the server for one client session, counted as active while it lives.
+    fn for_session(&self) -> Self {
+        let mut server = self.clone();
+        server.progress = self.progress.for_session();
+        server.session = self
+            .metrics
+            .as_ref()
+            .map(|metrics| Arc::new(metrics.session_started()));
+        server
+    }
+
+    fn record_operation(
+        &self,
+        method: &str,
+        target: Option<&str>,
+        error_type: Option<&str>,
+        started: Instant,
+    ) {
+        if let Some(metrics) = &self.metrics {
+            metrics.record_operation(method, target, error_type, started.elapsed());
+        }
+    }
+
+    // Readiness of this server's current tools: channel-backed tools need a
+    // publisher to be callable.
+    fn readiness(&self) -> (bool, serde_json::Value) {
+        let tool_set = self.tools.current();
+        let channel_tools = tool_set
+            .tools
+            .values()
+            .filter(|resolved| matches!(resolved.target, ResolvedToolTarget::Channel { .. }))
+            .count();
+        let publisher = self.publisher.is_some();
+        let ready = channel_tools == 0 || publisher;
+        let report = serde_json::json!({
+            "status": if ready { "ready" } else { "unavailable" },
+            "server": self.name,
+            "tools": tool_set.tools.len(),
+            "channelTools": channel_tools,
+            "publisher": publisher,
+        });
+        (ready, report)
+    }
+
+    // Liveness and readiness routes, per the server's health config.
+    fn health_router(&self) -> axum::Router {
+        let mut router = axum::Router::new();
+        if let Some(path) = &self.health.liveness_path {
+            router = router.route(
+                path,
+                axum::routing::get(|| {
+                    std::future::ready(axum::Json(serde_json::json!({ "status": "ok" })))
+                }),
+            );
+        }
+        if let Some(path) = &self.health.readiness_path {
+            let server = self.clone();
+            router = router.route(
+                path,
+                axum::routing::get(move || {
+                    let (ready, report) = server.readiness();
+                    let status = if ready {
+                        axum::http::StatusCode::OK
+                    } else {
+                        axum::http::StatusCode::SERVICE_UNAVAILABLE
+                    };
+                    std::future::ready((status, axum::Json(report)))
+                }),
+            );
+        }
+        router
+    }
+
+    fn record_validation_failure(&self, tool_name: &str, schema: &'static str) {
+        if let Some(metrics) = &self.metrics {
+            metrics.record_validation_failure(tool_name, schema);
         }
     }
 
-    /// Run the MCP server, listening for HTTP requests until the shutdown signal fires.
-    pub async fn run(self, mut shutdown: watch::Receiver<bool>) -> Result<()> {
+    // Serve the Prometheus endpoint on its own port, if one is configured,
+    // until the shutdown signal fires.
+    async fn spawn_metrics_listener(
+        &self,
+        mut shutdown: watch::Receiver<bool>,
+    ) -> Result<Option<JoinHandle<()>>> {
+        let Some((endpoint, port)) = self
+            .prometheus
+            .as_ref()
+            .and_then(|endpoint| Some((endpoint.clone(), endpoint.port()?)))
+        else {
+            return Ok(None);
+        };
+        let addr = SocketAddr::new(self.addr.ip(), port);
+        let tcp_listener = tokio::net::TcpListener::bind(addr).await?;
+        tracing::info!(
+            "Prometheus metrics endpoint: http://{addr}{}",
+            endpoint.path()
+        );
+        Ok(Some(tokio::spawn(async move {
+            let result = axum::serve(tcp_listener, endpoint.router())
+                .with_graceful_shutdown(async move {
+                    let _ = shutdown.changed().await;
+                })
+                .await;
+            if let Err(err) = result {
+                tracing::error!("Metrics server error: {err}");
+            }
+        })))
+    }
+
+    // Flush and stop the telemetry providers. Shutdown runs via spawn_blocking
+    // since BatchSpanProcessor.shutdown() calls block_on.
+    async fn shutdown_telemetry(
+        tracer_provider: Option<Arc<SdkTracerProvider>>,
+        meter_provider: Option<Arc<SdkMeterProvider>>,
+    ) {
+        if let Some(provider) = tracer_provider {
+            let _ = tokio::task::spawn_blocking(move || provider.shutdown()).await;
+        }
+        if let Some(provider) = meter_provider {
+            let _ = tokio::task::spawn_blocking(move || provider.shutdown()).await;
+        }
+    }
+
+    /// Run the MCP server on its configured transport until the shutdown signal fires.
+    pub async fn run(self, shutdown: watch::Receiver<bool>) -> Result<()> {
+        let metrics_listener = self.spawn_metrics_listener(shutdown.clone()).await?;
+        let result = match self.transport {
+            Transport::StreamableHttp => self.run_http(shutdown).await,
+            Transport::Stdio => self.run_stdio(shutdown).await,
+        };
+        // A stdio session can end before the shutdown signal.
+        if let Some(handle) = metrics_listener {
+            handle.abort();
+        }
+        result
+    }
+
+    // Serve a single session over stdin/stdout until the client disconnects or
+    // the shutdown signal fires.
+    async fn run_stdio(self, mut shutdown: watch::Receiver<bool>) -> Result<()> {
+        let tracer_provider = self.tracer_provider.clone();
+        let meter_provider = self.meter_provider.clone();
+
+        tracing::info!("Serving MCP over stdio");
+
+        let running = self.for_session().serve(rmcp::transport::stdio()).await?;
+        let cancellation_token = running.cancellation_token();
+
+        tokio::select! {
+            result = running.waiting() => {
+                match result {
+                    Ok(reason) => tracing::info!("stdio session ended: {reason:?}"),
+                    Err(err) => tracing::error!("stdio session error: {err}"),
+                }
+            }
+            _ = shutdown.changed() => {
+                tracing::info!("MCP server on stdio shutting down");
+                cancellation_token.cancel();
+            }
+        }
+
+        Self::shutdown_telemetry(tracer_provider, meter_provider).await;
+
+        Ok(())
+    }
+
+    // Listen for Streamable HTTP requests until the shutdown signal fires.
+    async fn run_http(self, mut shutdown: watch::Receiver<bool>) -> Result<()> {
         let addr = self.addr;
         let origin_policy = self.origin_policy.clone();
-        // Keep a handle to the tracer provider for shutdown.
+        let auth_policy = self.auth_policy.clone();
+        let tools = self.tools.clone();
+        let health_router = self.health_router();
+        // Served alongside /mcp unless it has its own port.
+        let prometheus = self
+            .prometheus
+            .clone()
+            .filter(|endpoint| endpoint.port().is_none());
+        // Keep handles to the telemetry providers for shutdown.
         let tracer_provider = self.tracer_provider.clone();
+        let meter_provider = self.meter_provider.clone();
 
+        // Called once per session, so each session holds its own copy.
         let service = StreamableHttpService::new(
-            move || Ok(self.clone()),
+            move || Ok(self.for_session()),
             LocalSessionManager::default().into(),
             Default::default(),
         );
 
-        let router = axum::Router::new().nest_service("/mcp", service).layer(
-            axum::middleware::from_fn_with_state(origin_policy, validate_origin),
-        );
+        let mut router = axum::Router::new().nest_service("/mcp", service);
+        if let Some(auth_policy) = auth_policy {
+            let oauth_config = auth_policy
+                .verifier()
+                .map(|verifier| verifier.config().clone());
+            // Only OAuth principals carry scopes. Added first so it runs after authentication.
+            if auth_policy.verifier().is_some() {
+                router = router.layer(axum::middleware::from_fn_with_state(
+                    (auth_policy.clone(), tools.clone()),
+                    check_tool_scopes,
+                ));
+            }
+            router = router.layer(axum::middleware::from_fn_with_state(
+                auth_policy,
+                authenticate,
+            ));
+            // Routed after the auth layer so clients can discover the authorization server.
+            // Scopes are read per request, so the metadata follows reloaded tools.
+            if let Some(oauth_config) = oauth_config {
+                router = router.route(
+                    PROTECTED_RESOURCE_METADATA_PATH,
+                    axum::routing::get(move || {
+                        let metadata =
+                            protected_resource_metadata(&oauth_config, &tools.scopes_supported());
+                        std::future::ready(axum::Json(metadata))
+                    }),
+                );
+            }
+        }
+        // Also routed after the auth layer, since scrapers do not present MCP credentials.
+        if let Some(prometheus) = prometheus {
+            tracing::info!(
+                "Prometheus metrics endpoint: http://{addr}{}",
+                prometheus.path()
+            );
+            router = router.merge(prometheus.router());
+        }
+        // Added last so Origin validation runs before authentication.
+        let router = router.layer(axum::middleware::from_fn_with_state(
+            origin_policy,
+            validate_origin,
+        ));
+        // Merged after both layers so orchestrator probes need neither an
+        // allowed Origin nor credentials.
+        let router = router.merge(health_router);
         let tcp_listener = tokio::net::TcpListener::bind(addr).await?;
 
         tracing::info!("Streamable HTTP endpoint: http://{addr}/mcp");
@@ -85,10 +543,7 @@ impl McpServer {
             }
         }
 
-        // Shutdown via spawn_blocking since BatchSpanProcessor.shutdown() calls block_on.
-        if let Some(provider) = tracer_provider {
-            let _ = tokio::task::spawn_blocking(move || provider.shutdown()).await;
-        }
+        Self::shutdown_telemetry(tracer_provider, meter_provider).await;
 
         Ok(())
     }
@@ -180,55 +635,182 @@ impl McpServer {
         Some((span, context))
     }
 
+    // Return the page of tools starting at `cursor` and the cursor for the next page.
+    //
+    // Cursors encode the tool set snapshot and an offset, so a cursor issued for one
+    // snapshot is rejected rather than silently skipping or repeating tools.
+    fn tools_page(
+        &self,
+        cursor: Option<&str>,
+    ) -> Result<(Vec<Tool>, Option<String>), rmcp::ErrorData> {
+        let tool_set = self.tools.current();
+        let offset = match cursor {
+            Some(cursor) => decode_cursor(cursor)
+                .filter(|(snapshot, offset)| {
+                    *snapshot == tool_set.snapshot && *offset <= tool_set.tools.len()
+                })
+                .map(|(_, offset)| offset)
+                .ok_or_else(|| {
+                    rmcp::ErrorData::invalid_params(format!("Invalid cursor: {cursor}"), None)
+                })?,
+            None => 0,
+        };
+        let page_size = self.page_size.unwrap_or(usize::MAX);
+        let tools: Vec<Tool> = tool_set
+            .tools
+            .values()
+            .skip(offset)
+            .take(page_size)
+            .map(|r| r.tool.clone())
+            .collect();
+        let next_offset = offset + tools.len();
+        let next_cursor = (next_offset < tool_set.tools.len())
+            .then(|| encode_cursor(tool_set.snapshot, next_offset));
+        Ok((tools, next_cursor))
+    }
+
+    // How long to wait before retrying, if the tool's rate limit for this
+    // session is exhausted. Stdio requests share a single session.
+    fn rate_limited(
+        &self,
+        tool_name: &str,
+        context: &RequestContext<RoleServer>,
+    ) -> Option<Duration> {
+        let tool_set = self.tools.current();
+        let limit = tool_set.tools.get(tool_name)?.rate_limit.as_ref()?;
+        let session_id = context
+            .extensions
+            .get::<axum::http::request::Parts>()
+            .and_then(|parts| parts.headers.get("MCP-Session-Id"))
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default();
+        let retry_after = self
+            .rate_limiter
+            .acquire(tool_name, session_id, limit, Instant::now())
+            .err()?;
+        if let Some(metrics) = &self.metrics {
+            metrics.record_rate_limited(tool_name);
+        }
+        Some(retry_after)
+    }
+
+    /// Call a tool outside of any MCP session, as `tools/call` would without auth.
+    pub(crate) async fn invoke_tool(
+        &self,
+        tool_name: &str,
+        arguments: &JsonObject,
+    ) -> CallToolResult {
+        let tool_set = self.tools.current();
+        self.handle_tool_call(
+            &tool_set,
+            tool_name,
+            arguments,
+            None,
+            &CancellationToken::new(),
+        )
+        .await
+        .unwrap_or_else(|interrupted| interrupted.into_result(tool_name))
+    }
+
+    // Validate arguments and invoke the tool's backend within its timeout.
+    // The caller holds `tool_set` for the whole call, so a concurrent reload
+    // cannot change the tool mid-call.
+    //
+    // Returns `Err` only when the timeout elapses or `ct` is cancelled; all
+    // other failures are reported as error results.
     async fn handle_tool_call(
         &self,
+        tool_set: &ToolSet,
         tool_name: &str,
         arguments: &JsonObject,
         context: Option<HashMap<String, String>>,
-    ) -> CallToolResult {
-        let Some(resolved) = self.tools.get(tool_name) else {
-            return CallToolResult::error(vec![Content::text(format!(
+        ct: &CancellationToken,
+    ) -> Result<CallToolResult, Interrupted> {
+        let Some(resolved) = tool_set.tools.get(tool_name) else {
+            return Ok(CallToolResult::error(vec![Content::text(format!(
                 "Tool not found: {tool_name}"
-            ))]);
+            ))]));
         };
 
+        let _active_call = self
+            .metrics
+            .as_ref()
+            .map(|metrics| metrics.call_started(tool_name));
+
         let args_value = serde_json::Value::Object(arguments.clone());
         if let Err(error) = resolved.input_validator.validate(&args_value) {
-            return CallToolResult::error(vec![Content::text(format!(
+            self.record_validation_failure(tool_name, "input");
+            return Ok(CallToolResult::error(vec![Content::text(format!(
                 "Invalid arguments for tool '{tool_name}': {error}"
-            ))]);
+            ))]));
         }
 
-        match &resolved.target {
-            ResolvedToolTarget::Component {
-                function,
-                component_name,
-            } => {
-                self.handle_component_call(
-                    &resolved.tool,
+        // Dropping the invocation future on timeout or cancellation abandons
+        // the pending component call or channel reply.
+        let invocation = async {
+            match &resolved.target {
+                ResolvedToolTarget::Component {
                     function,
                     component_name,
-                    arguments,
-                    context,
-                )
-                .await
+                    defaults,
+                    fixed,
+                } => {
+                    // Fixed values win over the caller's, which win over defaults.
+                    let mut arguments = arguments.clone();
+                    for (name, value) in defaults {
+                        arguments
+                            .entry(name.clone())
+                            .or_insert_with(|| value.clone());
+                    }
+                    arguments.extend(fixed.clone());
+                    self.handle_component_call(
+                        &resolved.tool,
+                        &resolved.output_validator,
+                        function,
+                        component_name,
+                        &arguments,
+                        context,
+                    )
+                    .await
+                }
+                ResolvedToolTarget::Channel { channel } => {
+                    self.handle_channel_call(
+                        &resolved.tool,
+                        &resolved.output_validator,
+                        channel,
+                        arguments,
+                        context,
+                    )
+                    .await
+                }
             }
-            ResolvedToolTarget::Channel { channel } => {
-                self.handle_channel_call(
-                    &resolved.tool,
-                    &resolved.output_validator,
-                    channel,
-                    arguments,
-                    context,
-                )
-                .await
+        };
+        let invocation = async {
+            match resolved.timeout {
+                Some(timeout) => tokio::time::timeout(timeout, invocation)
+                    .await
+                    .map_err(|_| Interrupted::Timeout(timeout)),
+                None => Ok(invocation.await),
             }
+        };
+        let result = tokio::select! {
+            biased;
+            _ = ct.cancelled() => Err(Interrupted::Cancelled),
+            result = invocation => result,
+        };
+        if let Err(Interrupted::Timeout(_)) = result
+            && let ResolvedToolTarget::Channel { channel } = &resolved.target
+            && let Some(metrics) = &self.metrics
+        {
+            metrics.record_channel_reply_timeout(tool_name, channel);
         }
+        result
     }
 
     async fn handle_component_call(
         &self,
         tool: &Tool,
+        output_validator: &Option<jsonschema::Validator>,
         function: &Function,
         component_name: &str,
         arguments: &JsonObject,
@@ -252,8 +834,26 @@ impl McpServer {
             .await
         {
             Ok(result) => {
+                if McpMapper::returns_content(function) {
+                    return match result_to_content(result) {
+                        Ok(content) => CallToolResult::success(content),
+                        Err(e) => CallToolResult::error(vec![Content::text(format!(
+                            "Tool '{}' returned invalid content: {e}",
+                            tool.name
+                        ))]),
+                    };
+                }
                 if tool.output_schema.is_some() {
                     let structured_content = self.result_to_structured_content(tool, result);
+                    if let Some(validator) = output_validator
+                        && let Err(error) = validator.validate(&structured_content)
+                    {
+                        self.record_validation_failure(&tool.name, "output");
+                        return CallToolResult::error(vec![Content::text(format!(
+                            "Result from tool '{}' does not conform to output-schema: {error}",
+                            tool.name
+                        ))]);
+                    }
                     CallToolResult::structured(structured_content)
                 } else {
                     let result_text = if result.is_string() {
@@ -264,7 +864,16 @@ impl McpServer {
                     CallToolResult::success(vec![Content::text(result_text)])
                 }
             }
-            Err(error) => CallToolResult::error(vec![Content::text(error.to_string())]),
+            Err(error) => {
+                // A structured WIT error arm is returned as `{ "error": E }`
+                // so agents can branch on the error case.
+                if McpMapper::has_structured_error(function)
+                    && let Some(ResultError(value)) = error.downcast_ref::<ResultError>()
+                {
+                    return CallToolResult::structured_error(serde_json::json!({ "error": value }));
+                }
+                CallToolResult::error(vec![Content::text(error.to_string())])
+            }
         }
     }
 
@@ -297,7 +906,9 @@ impl McpServer {
             headers.extend(ctx);
         }
 
-        let return_address = match publisher.publish_request(channel, body, headers).await {
+        // Interim replies are addressed to the caller by the same context.
+        let progress_context = headers.clone();
+        let mut return_address = match publisher.publish_request(channel, body, headers).await {
             Ok(ra) => ra,
             Err(e) => {
                 return CallToolResult::error(vec![Content::text(format!(
@@ -306,13 +917,31 @@ impl McpServer {
             }
         };
 
-        match return_address.take().await {
+        // Progress replies precede the one carrying the result.
+        let reply = loop {
+            match return_address.take().await {
+                Ok(reply) if reply.headers().contains_key(PROGRESS_REPLY_HEADER) => {
+                    match serde_json::from_slice::<ProgressReport>(reply.body()) {
+                        Ok(report) => {
+                            self.progress.report(&progress_context, report);
+                        }
+                        Err(e) => {
+                            tracing::warn!(channel, "Ignoring invalid progress reply: {e}");
+                        }
+                    }
+                }
+                reply => break reply,
+            }
+        };
+
+        match reply {
             Ok(reply) => {
                 let body = String::from_utf8_lossy(reply.body()).to_string();
                 if let Some(validator) = output_validator {
                     match serde_json::from_str::<serde_json::Value>(&body) {
                         Ok(json) => {
                             if let Err(error) = validator.validate(&json) {
+                                self.record_validation_failure(&tool.name, "output");
                                 return CallToolResult::error(vec![Content::text(format!(
                                     "Reply from channel '{channel}' does not conform to output-schema: {error}"
                                 ))]);
@@ -333,43 +962,502 @@ impl McpServer {
             ))]),
         }
     }
-}
 
-// Extract gen_ai semantic convention attributes from the request context.
-fn request_attributes(context: &RequestContext<RoleServer>) -> Vec<KeyValue> {
-    let mut attrs = vec![
-        KeyValue::new("jsonrpc.request.id", context.id.to_string()),
-        KeyValue::new("network.transport", "tcp"),
-        KeyValue::new("network.protocol.name", "http"),
-    ];
-
-    if let Some(parts) = context.extensions.get::<axum::http::request::Parts>() {
-        if let Some(session_id) = parts
-            .headers
-            .get("MCP-Session-Id")
-            .and_then(|v| v.to_str().ok())
+    async fn handle_resource_read(
+        &self,
+        uri: &str,
+        context: Option<HashMap<String, String>>,
+    ) -> Result<ReadResourceResult, rmcp::ErrorData> {
+        let Some((resource, bindings)) = self
+            .resources
+            .iter()
+            .find_map(|r| r.uri_template.match_uri(uri).map(|b| (r, b)))
+        else {
+            return Err(rmcp::ErrorData::resource_not_found(
+                format!("Resource not found: {uri}"),
+                None,
+            ));
+        };
+
+        // Bind URI template variables to parameters, converting to the parameter's JSON type.
+        let json_args: Vec<serde_json::Value> = resource
+            .function
+            .params()
+            .iter()
+            .map(|param| match bindings.get(&param.name) {
+                Some(value) => string_to_param_value(value, &param.json_schema),
+                None => serde_json::Value::Null,
+            })
+            .collect();
+
+        match self
+            .invoker
+            .invoke(
+                &resource.component_name,
+                &resource.function.key(),
+                json_args,
+                context,
+                None,
+            )
+            .await
         {
-            attrs.push(KeyValue::new("mcp.session.id", session_id.to_string()));
+            Ok(result) => Ok(ReadResourceResult {
+                contents: vec![result_to_resource_contents(
+                    uri,
+                    resource.mime_type.as_deref(),
+                    result,
+                )],
+            }),
+            Err(error) => Err(rmcp::ErrorData::internal_error(
+                format!("Failed to read resource '{uri}': {error}"),
+                None,
+            )),
         }
-        if let Some(version) = parts
-            .headers
-            .get("MCP-Protocol-Version")
-            .and_then(|v| v.to_str().ok())
-        {
-            attrs.push(KeyValue::new("mcp.protocol.version", version.to_string()));
+    }
+
+    async fn handle_prompt_get(
+        &self,
+        name: &str,
+        arguments: &JsonObject,
+        context: Option<HashMap<String, String>>,
+    ) -> Result<GetPromptResult, rmcp::ErrorData> {
+        let Some(resolved) = self.prompts.iter().find(|p| p.prompt.name == name) else {
+            return Err(rmcp::ErrorData::invalid_params(
+                format!("Prompt not found: {name}"),
+                None,
+            ));
+        };
+
+        // Prompt arguments arrive as strings per the MCP spec.
+        let mut json_args = Vec::new();
+        for param in resolved.function.params() {
+            let value = match arguments.get(&param.name) {
+                Some(serde_json::Value::String(s)) => string_to_param_value(s, &param.json_schema),
+                Some(other) => other.clone(),
+                None if param.is_optional => serde_json::Value::Null,
+                None => {
+                    return Err(rmcp::ErrorData::invalid_params(
+                        format!("Prompt '{name}' missing required argument '{}'", param.name),
+                        None,
+                    ));
+                }
+            };
+            json_args.push(value);
         }
-        if let Some(connect_info) = parts
-            .extensions
-            .get::<axum::extract::ConnectInfo<SocketAddr>>()
+
+        let result = self
+            .invoker
+            .invoke(
+                &resolved.component_name,
+                &resolved.function.key(),
+                json_args,
+                context,
+                None,
+            )
+            .await
+            .map_err(|e| {
+                rmcp::ErrorData::internal_error(format!("Failed to get prompt '{name}': {e}"), None)
+            })?;
+
+        let messages = result_to_prompt_messages(result)
+            .map_err(|e| rmcp::ErrorData::internal_error(format!("Prompt '{name}' {e}"), None))?;
+
+        Ok(GetPromptResult {
+            description: resolved.prompt.description.clone(),
+            messages,
+        })
+    }
+}
+
+// Convert a prompt function result to messages:
+// - string => a single user message
+// - list of records with `role` and `content` => one message per record
+fn result_to_prompt_messages(
+    result: serde_json::Value,
+) -> std::result::Result<Vec<PromptMessage>, String> {
+    match result {
+        serde_json::Value::String(text) => {
+            Ok(vec![PromptMessage::new_text(PromptMessageRole::User, text)])
+        }
+        serde_json::Value::Array(items) => items
+            .into_iter()
+            .map(|item| {
+                let role = match item.get("role").and_then(|r| r.as_str()) {
+                    Some("user") => PromptMessageRole::User,
+                    Some("assistant") => PromptMessageRole::Assistant,
+                    other => {
+                        return Err(format!("returned a message with invalid role: {other:?}"));
+                    }
+                };
+                let content = item
+                    .get("content")
+                    .and_then(|c| c.as_str())
+                    .ok_or_else(|| "returned a message without string content".to_string())?;
+                Ok(PromptMessage::new_text(role, content))
+            })
+            .collect(),
+        other => Err(format!(
+            "must return a string or a list of messages, got {other}"
+        )),
+    }
+}
+
+// Convert a string value (URI template binding or prompt argument) to JSON according
+// to the parameter's schema type. Values that do not parse as the declared type pass
+// through as strings and are rejected by the component invocation.
+fn string_to_param_value(value: &str, schema: &serde_json::Value) -> serde_json::Value {
+    match schema.get("type").and_then(|t| t.as_str()) {
+        Some("integer") | Some("number") => serde_json::from_str::<serde_json::Number>(value)
+            .map(serde_json::Value::Number)
+            .unwrap_or_else(|_| serde_json::Value::String(value.to_string())),
+        Some("boolean") => value
+            .parse::<bool>()
+            .map(serde_json::Value::Bool)
+            .unwrap_or_else(|_| serde_json::Value::String(value.to_string())),
+        _ => serde_json::Value::String(value.to_string()),
+    }
+}
+
+// Convert a `content-block` value, or a list of them, to MCP content.
+fn result_to_content(result: serde_json::Value) -> Result<Vec<Content>, String> {
+    match result {
+        serde_json::Value::Array(blocks) => blocks.into_iter().map(content_block).collect(),
+        block => Ok(vec![content_block(block)?]),
+    }
+}
+
+// WIT variants arrive as `{ "type": <case>, "value": <payload> }`.
+fn content_block(block: serde_json::Value) -> Result<Content, String> {
+    let case = block["type"].as_str().unwrap_or_default();
+    let value = &block["value"];
+    let field = |name: &str| value[name].as_str().map(|s| s.to_string());
+    let required = |name: &str| field(name).ok_or_else(|| format!("{case} is missing '{name}'"));
+    match case {
+        "text" => Ok(Content::text(
+            value.as_str().ok_or("text content must be a string")?,
+        )),
+        "image" => Ok(Content::image(
+            bytes_to_base64(&value["data"])?,
+            required("mime-type")?,
+        )),
+        "audio" => Ok(RawContent::Audio(RawAudioContent {
+            data: bytes_to_base64(&value["data"])?,
+            mime_type: required("mime-type")?,
+        })
+        .no_annotation()),
+        "resource-link" => {
+            let mut resource = RawResource::new(required("uri")?, required("name")?);
+            resource.description = field("description");
+            resource.mime_type = field("mime-type");
+            Ok(Content::resource_link(resource))
+        }
+        "resource" => {
+            let uri = required("uri")?;
+            let mime_type = field("mime-type");
+            let contents = &value["contents"];
+            let resource = match contents["type"].as_str() {
+                Some("text") => ResourceContents::TextResourceContents {
+                    uri,
+                    mime_type,
+                    text: contents["value"]
+                        .as_str()
+                        .ok_or("text resource contents must be a string")?
+                        .to_string(),
+                    meta: None,
+                },
+                Some("blob") => ResourceContents::BlobResourceContents {
+                    uri,
+                    mime_type,
+                    blob: bytes_to_base64(&contents["value"])?,
+                    meta: None,
+                },
+                _ => return Err("resource contents must be text or blob".to_string()),
+            };
+            Ok(Content::resource(resource))
+        }
+        other => Err(format!("unknown content-block case '{other}'")),
+    }
+}
+
+// Encode a WIT `list<u8>` as base64.
+fn bytes_to_base64(value: &serde_json::Value) -> Result<String, String> {
+    let bytes = value
+        .as_array()
+        .ok_or("binary data must be a list of bytes")?
+        .iter()
+        .map(|v| {
+            v.as_u64()
+                .and_then(|n| u8::try_from(n).ok())
+                .ok_or("binary data must be a list of bytes")
+        })
+        .collect::<Result<Vec<u8>, _>>()?;
+    Ok(base64::engine::general_purpose::STANDARD.encode(bytes))
+}
+
+// Error result for a call over its rate limit. The wait is also given in
+// `_meta.retryAfterMs` so clients can back off without parsing the text.
+fn rate_limited_result(tool_name: &str, retry_after: Duration) -> CallToolResult {
+    let mut result = CallToolResult::error(vec![Content::text(format!(
+        "Rate limit exceeded for tool '{tool_name}'; retry after {retry_after:?}"
+    ))]);
+    let mut meta = Meta::new();
+    meta.insert(
+        "retryAfterMs".to_string(),
+        serde_json::json!(retry_after.as_millis() as u64),
+    );
+    result.meta = Some(meta);
+    result
+}
+
+// Convert a component result to resource contents:
+// - string => text
+// - list<u8> => base64 blob
+// - anything else => JSON text
+fn result_to_resource_contents(
+    uri: &str,
+    mime_type: Option<&str>,
+    result: serde_json::Value,
+) -> ResourceContents {
+    match result {
+        serde_json::Value::String(text) => ResourceContents::TextResourceContents {
+            uri: uri.to_string(),
+            mime_type: Some(mime_type.unwrap_or("text/plain").to_string()),
+            text,
+            meta: None,
+        },
+        serde_json::Value::Array(ref items)
+            if !items.is_empty()
+                && items
+                    .iter()
+                    .all(|v| v.as_u64().is_some_and(|n| n <= u8::MAX as u64)) =>
         {
-            attrs.push(KeyValue::new(
-                "client.address",
-                connect_info.0.ip().to_string(),
-            ));
-            attrs.push(KeyValue::new("client.port", connect_info.0.port() as i64));
+            let bytes: Vec<u8> = items
+                .iter()
+                .filter_map(|v| v.as_u64().map(|n| n as u8))
+                .collect();
+            ResourceContents::BlobResourceContents {
+                uri: uri.to_string(),
+                mime_type: Some(mime_type.unwrap_or("application/octet-stream").to_string()),
+                blob: base64::engine::general_purpose::STANDARD.encode(bytes),
+                meta: None,
+            }
+        }
+        other => ResourceContents::TextResourceContents {
+            uri: uri.to_string(),
+            mime_type: Some(mime_type.unwrap_or("application/json").to_string()),
+            text: serde_json::to_string_pretty(&other).unwrap_or_else(|_| other.to_string()),
+            meta: None,
+        },
+    }
+}
+
+/// Why a tool call ended before its backend produced a result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Interrupted {
+    /// The tool's configured timeout elapsed.
+    Timeout(std::time::Duration),
+    /// The client sent `notifications/cancelled` for the request.
+    Cancelled,
+}
+
+impl Interrupted {
+    fn error_type(self) -> &'static str {
+        match self {
+            Interrupted::Timeout(_) => "timeout",
+            Interrupted::Cancelled => "cancelled",
         }
     }
 
+    fn into_result(self, tool_name: &str) -> CallToolResult {
+        let message = match self {
+            Interrupted::Timeout(timeout) => {
+                format!("Tool '{tool_name}' timed out after {timeout:?}")
+            }
+            Interrupted::Cancelled => format!("Tool '{tool_name}' was cancelled"),
+        };
+        CallToolResult::error(vec![Content::text(message)])
+    }
+}
+
+// Fingerprint the tool set by name and definition.
+fn tools_snapshot(tools: &BTreeMap<String, ResolvedTool>) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for (name, resolved) in tools {
+        name.hash(&mut hasher);
+        serde_json::to_string(&resolved.tool)
+            .unwrap_or_default()
+            .hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+fn encode_cursor(snapshot: u64, offset: usize) -> String {
+    base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(format!("{snapshot:016x}:{offset}"))
+}
+
+fn decode_cursor(cursor: &str) -> Option<(u64, usize)> {
+    let bytes = base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(cursor)
+        .ok()?;
+    let decoded = String::from_utf8(bytes).ok()?;
+    let (snapshot, offset) = decoded.split_once(':')?;
+    Some((
+        u64::from_str_radix(snapshot, 16).ok()?,
+        offset.parse().ok()?,
+    ))
+}
+
+// Largest request body buffered to find the tools it calls, matching axum's
+// default body limit.
+const MAX_SCOPED_REQUEST_BYTES: usize = 2 * 1024 * 1024;
+
+// Reject `tools/call` requests whose access token lacks a tool's scopes with
+// 403 and an `insufficient_scope` challenge, as the MCP authorization spec
+// requires, before the request reaches the MCP service.
+async fn check_tool_scopes(
+    axum::extract::State((auth_policy, tools)): axum::extract::State<(AuthPolicy, ToolsHandle)>,
+    request: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> axum::response::Response {
+    let granted = request
+        .extensions()
+        .get::<Principal>()
+        .and_then(|principal| principal.scopes.clone());
+    let Some(granted) = granted.filter(|_| request.method() == axum::http::Method::POST) else {
+        return next.run(request).await;
+    };
+    let (parts, body) = request.into_parts();
+    let bytes = match buffer_body(body, MAX_SCOPED_REQUEST_BYTES).await {
+        Ok(bytes) => bytes,
+        Err(response) => return response,
+    };
+    let missing = tools.missing_scopes(&called_tools(&bytes), &granted);
+    if !missing.is_empty() {
+        tracing::warn!(
+            scopes = missing.join(" "),
+            "Rejected tool call with insufficient scope"
+        );
+        return auth_policy.insufficient_scope(&missing);
+    }
+    next.run(axum::extract::Request::from_parts(
+        parts,
+        axum::body::Body::from(bytes),
+    ))
+    .await
+}
+
+// Buffer a request body of at most `limit` bytes, or the response rejecting it.
+async fn buffer_body(
+    body: axum::body::Body,
+    limit: usize,
+) -> Result<axum::body::Bytes, axum::response::Response> {
+    axum::body::to_bytes(body, limit).await.map_err(|e| {
+        let e = e.into_inner();
+        let response = if e.is::<http_body_util::LengthLimitError>() {
+            (
+                axum::http::StatusCode::PAYLOAD_TOO_LARGE,
+                format!("Request body exceeds {limit} bytes"),
+            )
+        } else {
+            (
+                axum::http::StatusCode::BAD_REQUEST,
+                format!("Bad request: {e}"),
+            )
+        };
+        axum::response::IntoResponse::into_response(response)
+    })
+}
+
+// Names of the tools called by a JSON-RPC message or batch.
+fn called_tools(body: &[u8]) -> Vec<String> {
+    let messages = match serde_json::from_slice::<serde_json::Value>(body) {
+        Ok(serde_json::Value::Array(messages)) => messages,
+        Ok(message) => vec![message],
+        Err(_) => return Vec::new(),
+    };
+    messages
+        .iter()
+        .filter(|message| message.get("method").and_then(|m| m.as_str()) == Some("tools/call"))
+        .filter_map(|message| {
+            message
+                .pointer("/params/name")?
+                .as_str()
+                .map(str::to_string)
+        })
+        .collect()
+}
+
+// Session, client and principal of a request, for the audit log.
+fn request_caller(context: &RequestContext<RoleServer>) -> Caller {
+    let parts = context.extensions.get::<axum::http::request::Parts>();
+    Caller {
+        session_id: parts
+            .and_then(|parts| parts.headers.get("MCP-Session-Id"))
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string),
+        client: context
+            .peer
+            .peer_info()
+            .map(|info| info.client_info.clone()),
+        principal: parts
+            .and_then(|parts| parts.extensions.get::<Principal>())
+            .map(|principal| principal.name.clone()),
+    }
+}
+
+// Component function or channel a tool call was routed to, for the audit log.
+fn audit_target(target: &ResolvedToolTarget) -> serde_json::Value {
+    match target {
+        ResolvedToolTarget::Component {
+            function,
+            component_name,
+            ..
+        } => serde_json::json!({ "component": component_name, "function": function.key() }),
+        ResolvedToolTarget::Channel { channel } => serde_json::json!({ "channel": channel }),
+    }
+}
+
+// Extract gen_ai semantic convention attributes from the request context.
+fn request_attributes(context: &RequestContext<RoleServer>) -> Vec<KeyValue> {
+    let mut attrs = vec![KeyValue::new("jsonrpc.request.id", context.id.to_string())];
+
+    // HTTP request parts are only present for Streamable HTTP. Otherwise the
+    // request arrived over stdio.
+    let Some(parts) = context.extensions.get::<axum::http::request::Parts>() else {
+        attrs.push(KeyValue::new("network.transport", "pipe"));
+        return attrs;
+    };
+    attrs.push(KeyValue::new("network.transport", "tcp"));
+    attrs.push(KeyValue::new("network.protocol.name", "http"));
+
+    if let Some(session_id) = parts
+        .headers
+        .get("MCP-Session-Id")
+        .and_then(|v| v.to_str().ok())
+    {
+        attrs.push(KeyValue::new("mcp.session.id", session_id.to_string()));
+    }
+    if let Some(version) = parts
+        .headers
+        .get("MCP-Protocol-Version")
+        .and_then(|v| v.to_str().ok())
+    {
+        attrs.push(KeyValue::new("mcp.protocol.version", version.to_string()));
+    }
+    if let Some(connect_info) = parts
+        .extensions
+        .get::<axum::extract::ConnectInfo<SocketAddr>>()
+    {
+        attrs.push(KeyValue::new(
+            "client.address",
+            connect_info.0.ip().to_string(),
+        ));
+        attrs.push(KeyValue::new("client.port", connect_info.0.port() as i64));
+    }
+    if let Some(principal) = parts.extensions.get::<Principal>() {
+        attrs.push(KeyValue::new("enduser.id", principal.name.clone()));
+    }
+
     attrs
 }
 
@@ -379,6 +1467,7 @@ impl ServerHandler for McpServer {
         request: CallToolRequestParams,
         context: RequestContext<RoleServer>,
     ) -> Result<CallToolResult, rmcp::ErrorData> {
+        let started = Instant::now();
         let tool_name = &request.name;
         let arguments = request.arguments.unwrap_or_default();
 
@@ -395,20 +1484,86 @@ impl ServerHandler for McpServer {
             KeyValue::new("gen_ai.tool.name", tool_name.to_string()),
         ];
         attrs.extend(request_attributes(&context));
+        // Dispatch and audit the call against the same tools, even across a reload.
+        let tool_set = self.tools.current();
+        let retry_after = self.rate_limited(tool_name, &context);
+        // Cancelled by rmcp when the client sends notifications/cancelled.
+        let ct = context.ct.clone();
+        let progress_token = context.meta.get_progress_token();
+        // Reports for this token reach the client until the call returns.
+        let progress_route = progress_token
+            .clone()
+            .map(|token| self.progress.open(token, context.peer.clone()));
+
+        let caller = self.audit.as_ref().map(|_| request_caller(&context));
 
         let span_ctx = self.start_mcp_span("tools/call", Some(tool_name), attrs, meta);
 
-        let context = span_ctx.as_ref().map(|(_, ctx)| ctx.clone());
+        let mut context = span_ctx.as_ref().map(|(_, ctx)| ctx.clone());
+        // Lets components and channel consumers correlate progress with the request.
+        if let Some(token) = &progress_token {
+            context
+                .get_or_insert_default()
+                .extend(self.progress.context_entries(token));
+        }
+
+        let (mut span, result, error_type) = {
+            let (result, error_type) = match retry_after {
+                Some(retry_after) => (rate_limited_result(tool_name, retry_after), "rate_limited"),
+                None => match self
+                    .handle_tool_call(&tool_set, tool_name, &arguments, context, &ct)
+                    .await
+                {
+                    Ok(result) => (result, "tool_error"),
+                    Err(interrupted) => {
+                        (interrupted.into_result(tool_name), interrupted.error_type())
+                    }
+                },
+            };
+            (span_ctx.map(|(span, _)| span), result, error_type)
+        };
+        if let Some(route) = progress_route {
+            route.close().await;
+        }
 
-        let (mut span, result) = {
-            let result = self.handle_tool_call(tool_name, &arguments, context).await;
-            (span_ctx.map(|(span, _)| span), result)
+        let is_error = result.is_error.unwrap_or(false);
+        let tool_label = if tool_set.tools.contains_key(tool_name.as_ref()) {
+            tool_name.as_ref()
+        } else {
+            UNKNOWN_TOOL
         };
+        self.record_operation(
+            "tools/call",
+            Some(tool_label),
+            is_error.then_some(error_type),
+            started,
+        );
+        if let (Some(audit), Some(caller)) = (&self.audit, &caller) {
+            let target = tool_set
+                .tools
+                .get(tool_name.as_ref())
+                .map(|resolved| audit_target(&resolved.target));
+            audit
+                .record(AuditEvent {
+                    caller,
+                    tool: tool_name,
+                    target,
+                    arguments: &arguments,
+                    duration: started.elapsed(),
+                    outcome: if is_error { error_type } else { "success" },
+                })
+                .await;
+        }
 
         if let Some(ref mut span) = span {
-            if result.is_error.unwrap_or(false) {
-                span.set_status(Status::error(""));
-                span.set_attribute(KeyValue::new("error.type", "tool_error"));
+            if is_error {
+                let description = if error_type == "cancelled" {
+                    "cancelled by client"
+                } else {
+                    ""
+                };
+                span.set_status(Status::error(description));
+                span.set_attribute(KeyValue::new("error.type", error_type));
             }
             span.end();
         }
@@ -420,18 +1575,221 @@ impl ServerHandler for McpServer {
         &self,
         request: InitializeRequestParams,
         context: RequestContext<RoleServer>,
-    ) -> Result<InitializeResult, rmcp::ErrorData> {
+    ) -> Result<InitializeResult, rmcp::ErrorData> {
+        let started = Instant::now();
+        let meta = if context.meta.0.is_empty() {
+            None
+        } else {
+            Some(&context.meta)
+        };
+        let span_ctx = self.start_mcp_span("initialize", None, request_attributes(&context), meta);
+
+        if context.peer.peer_info().is_none() {
+            context.peer.set_peer_info(request);
+        }
+        if self.tools_list_changed {
+            self.tools.register(context.peer.clone());
+        }
+        let result = self.get_info();
+
+        self.record_operation("initialize", None, None, started);
+
+        if let Some((mut span, _)) = span_ctx {
+            span.end();
+        }
+
+        Ok(result)
+    }
+
+    async fn list_tools(
+        &self,
+        request: Option<PaginatedRequestParams>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<ListToolsResult, rmcp::ErrorData> {
+        let started = Instant::now();
+        let meta = if context.meta.0.is_empty() {
+            None
+        } else {
+            Some(&context.meta)
+        };
+        let span_ctx = self.start_mcp_span("tools/list", None, request_attributes(&context), meta);
+
+        let cursor = request.as_ref().and_then(|r| r.cursor.as_deref());
+        let result = self
+            .tools_page(cursor)
+            .map(|(tools, next_cursor)| ListToolsResult {
+                tools,
+                next_cursor,
+                meta: None,
+            });
+
+        self.record_operation(
+            "tools/list",
+            None,
+            result.is_err().then_some("invalid_cursor"),
+            started,
+        );
+
+        if let Some((mut span, _)) = span_ctx {
+            if result.is_err() {
+                span.set_status(Status::error(""));
+                span.set_attribute(KeyValue::new("error.type", "invalid_cursor"));
+            }
+            span.end();
+        }
+
+        result
+    }
+
+    async fn list_resources(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<ListResourcesResult, rmcp::ErrorData> {
+        let started = Instant::now();
+        let meta = if context.meta.0.is_empty() {
+            None
+        } else {
+            Some(&context.meta)
+        };
+        let span_ctx =
+            self.start_mcp_span("resources/list", None, request_attributes(&context), meta);
+
+        let resources = self
+            .resources
+            .iter()
+            .filter(|r| r.uri_template.is_concrete())
+            .map(|r| {
+                let mut resource = RawResource::new(r.uri_template.as_str(), r.name.clone());
+                resource.description = r.description.clone();
+                resource.mime_type = r.mime_type.clone();
+                resource.no_annotation()
+            })
+            .collect();
+        let result = ListResourcesResult {
+            resources,
+            next_cursor: None,
+            meta: None,
+        };
+
+        self.record_operation("resources/list", None, None, started);
+
+        if let Some((mut span, _)) = span_ctx {
+            span.end();
+        }
+
+        Ok(result)
+    }
+
+    async fn list_resource_templates(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<ListResourceTemplatesResult, rmcp::ErrorData> {
+        let started = Instant::now();
+        let meta = if context.meta.0.is_empty() {
+            None
+        } else {
+            Some(&context.meta)
+        };
+        let span_ctx = self.start_mcp_span(
+            "resources/templates/list",
+            None,
+            request_attributes(&context),
+            meta,
+        );
+
+        let resource_templates = self
+            .resources
+            .iter()
+            .filter(|r| !r.uri_template.is_concrete())
+            .map(|r| {
+                RawResourceTemplate {
+                    uri_template: r.uri_template.as_str().to_string(),
+                    name: r.name.clone(),
+                    title: None,
+                    description: r.description.clone(),
+                    mime_type: r.mime_type.clone(),
+                    icons: None,
+                }
+                .no_annotation()
+            })
+            .collect();
+        let result = ListResourceTemplatesResult {
+            resource_templates,
+            next_cursor: None,
+            meta: None,
+        };
+
+        self.record_operation("resources/templates/list", None, None, started);
+
+        if let Some((mut span, _)) = span_ctx {
+            span.end();
+        }
+
+        Ok(result)
+    }
+
+    async fn read_resource(
+        &self,
+        request: ReadResourceRequestParams,
+        context: RequestContext<RoleServer>,
+    ) -> Result<ReadResourceResult, rmcp::ErrorData> {
+        let started = Instant::now();
+        let meta = if context.meta.0.is_empty() {
+            None
+        } else {
+            Some(&context.meta)
+        };
+
+        let mut attrs = vec![KeyValue::new("mcp.resource.uri", request.uri.clone())];
+        attrs.extend(request_attributes(&context));
+
+        let span_ctx = self.start_mcp_span("resources/read", Some(&request.uri), attrs, meta);
+        let propagation = span_ctx.as_ref().map(|(_, ctx)| ctx.clone());
+
+        let result = self.handle_resource_read(&request.uri, propagation).await;
+
+        self.record_operation(
+            "resources/read",
+            None,
+            result.is_err().then_some("resource_error"),
+            started,
+        );
+
+        if let Some((mut span, _)) = span_ctx {
+            if result.is_err() {
+                span.set_status(Status::error(""));
+                span.set_attribute(KeyValue::new("error.type", "resource_error"));
+            }
+            span.end();
+        }
+
+        result
+    }
+
+    async fn list_prompts(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<ListPromptsResult, rmcp::ErrorData> {
+        let started = Instant::now();
         let meta = if context.meta.0.is_empty() {
             None
         } else {
             Some(&context.meta)
         };
-        let span_ctx = self.start_mcp_span("initialize", None, request_attributes(&context), meta);
+        let span_ctx =
+            self.start_mcp_span("prompts/list", None, request_attributes(&context), meta);
 
-        if context.peer.peer_info().is_none() {
-            context.peer.set_peer_info(request);
-        }
-        let result = self.get_info();
+        let prompts = self.prompts.iter().map(|p| p.prompt.clone()).collect();
+        let result = ListPromptsResult {
+            prompts,
+            next_cursor: None,
+            meta: None,
+        };
+
+        self.record_operation("prompts/list", None, None, started);
 
         if let Some((mut span, _)) = span_ctx {
             span.end();
@@ -440,34 +1798,65 @@ impl ServerHandler for McpServer {
         Ok(result)
     }
 
-    async fn list_tools(
+    async fn get_prompt(
         &self,
-        _request: Option<PaginatedRequestParams>,
+        request: GetPromptRequestParams,
         context: RequestContext<RoleServer>,
-    ) -> Result<ListToolsResult, rmcp::ErrorData> {
+    ) -> Result<GetPromptResult, rmcp::ErrorData> {
+        let started = Instant::now();
+        let prompt_name = &request.name;
+        let arguments = request.arguments.unwrap_or_default();
+
         let meta = if context.meta.0.is_empty() {
             None
         } else {
             Some(&context.meta)
         };
-        let span_ctx = self.start_mcp_span("tools/list", None, request_attributes(&context), meta);
 
-        let tools = self.tools.values().map(|r| r.tool.clone()).collect();
-        let result = ListToolsResult {
-            tools,
-            next_cursor: None,
-            meta: None,
-        };
+        let mut attrs = vec![KeyValue::new("gen_ai.prompt.name", prompt_name.to_string())];
+        attrs.extend(request_attributes(&context));
+
+        let span_ctx = self.start_mcp_span("prompts/get", Some(prompt_name), attrs, meta);
+        let propagation = span_ctx.as_ref().map(|(_, ctx)| ctx.clone());
+
+        let result = self
+            .handle_prompt_get(prompt_name, &arguments, propagation)
+            .await;
+
+        self.record_operation(
+            "prompts/get",
+            None,
+            result.is_err().then_some("prompt_error"),
+            started,
+        );
 
         if let Some((mut span, _)) = span_ctx {
+            if result.is_err() {
+                span.set_status(Status::error(""));
+                span.set_attribute(KeyValue::new("error.type", "prompt_error"));
+            }
             span.end();
         }
 
-        Ok(result)
+        result
     }
 
     fn get_info(&self) -> ServerInfo {
-        ServerInfo::new(ServerCapabilities::builder().enable_tools().build())
+        let mut capabilities = if self.tools_list_changed {
+            ServerCapabilities::builder()
+                .enable_tools()
+                .enable_tool_list_changed()
+                .build()
+        } else {
+            ServerCapabilities::builder().enable_tools().build()
+        };
+        if !self.resources.is_empty() {
+            capabilities.resources = Some(ResourcesCapability::default());
+        }
+        if !self.prompts.is_empty() {
+            capabilities.prompts = Some(PromptsCapability::default());
+        }
+        ServerInfo::new(capabilities)
             .with_server_info(
                 rmcp::model::Implementation::new("modulewise-toolbelt", env!("CARGO_PKG_VERSION"))
                     .with_title("Modulewise Toolbelt")
@@ -477,7 +1866,7 @@ impl ServerHandler for McpServer {
                 "This server provides {} tools. \
                 Each tool has typed inputs and outputs described by its schema. \
                 Call tools with their required parameters.",
-                self.tools.len()
+                self.tools.current().tools.len()
             ))
     }
 }
@@ -520,10 +1909,14 @@ pub fn build_tracer_provider(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::mapper::McpMapper;
+    use crate::mapper::{ERROR_SCHEMA_META_KEY, McpMapper};
+    use crate::progress::PROGRESS_CAPABILITY;
     use composable_runtime::Runtime;
-    use rmcp::model::ClientInfo;
-    use rmcp::{ClientHandler, ServiceExt};
+    use rmcp::model::{
+        ClientInfo, NumberOrString, ProgressNotificationParam, ProgressToken, PromptMessageContent,
+    };
+    use rmcp::service::NotificationContext;
+    use rmcp::{ClientHandler, RoleClient, ServiceExt};
     use std::io::Write as _;
     use tempfile::Builder;
 
@@ -561,7 +1954,7 @@ mod tests {
     // Build an McpServer from a Runtime by auto-discovering all components.
     fn build_test_server(runtime: &Runtime) -> McpServer {
         let invoker = runtime.invoker();
-        let mut tools = HashMap::new();
+        let mut tools = BTreeMap::new();
 
         for component in runtime.list_components(None) {
             for function in component.functions.values() {
@@ -572,6 +1965,8 @@ mod tests {
                 let target = ResolvedToolTarget::Component {
                     function: Box::new(function.clone()),
                     component_name: component.metadata.name.clone(),
+                    defaults: serde_json::Map::new(),
+                    fixed: serde_json::Map::new(),
                 };
                 tools.insert(
                     tool_name,
@@ -580,6 +1975,9 @@ mod tests {
                         input_validator,
                         output_validator: None,
                         target,
+                        required_scopes: Vec::new(),
+                        timeout: None,
+                        rate_limit: None,
                     },
                 );
             }
@@ -733,4 +2131,794 @@ mod tests {
         assert!(text.contains("Tool not found"));
         assert!(text.contains("nonexistent-tool"));
     }
+
+    #[tokio::test]
+    async fn test_unknown_tool_metric_label() {
+        use opentelemetry_sdk::metrics::data::{AggregatedMetrics, MetricData};
+        use opentelemetry_sdk::metrics::{InMemoryMetricExporter, PeriodicReader};
+
+        let exporter = InMemoryMetricExporter::default();
+        let provider = SdkMeterProvider::builder()
+            .with_reader(PeriodicReader::builder(exporter.clone()).build())
+            .build();
+        let wasm = create_wasm(add_two_wat());
+        let runtime = build_runtime(wasm.path()).await;
+        let server = build_test_server(&runtime).with_metrics(Some(provider.clone()));
+        let client = setup_test_client(server).await;
+
+        let tool_name = client.list_tools(None).await.unwrap().tools[0].name.clone();
+        for name in ["made-up-1", "made-up-2", &tool_name] {
+            let request =
+                CallToolRequestParams::new(name.to_string()).with_arguments(args!({"x": 1}));
+            client.call_tool(request).await.unwrap();
+        }
+        provider.force_flush().unwrap();
+
+        let mut labels: Vec<String> = exporter
+            .get_finished_metrics()
+            .unwrap()
+            .iter()
+            .flat_map(|rm| rm.scope_metrics())
+            .flat_map(|scope| scope.metrics())
+            .filter(|metric| metric.name() == "mcp.server.tool.calls")
+            .flat_map(|metric| match metric.data() {
+                AggregatedMetrics::U64(MetricData::Sum(sum)) => sum
+                    .data_points()
+                    .flat_map(|p| p.attributes().cloned().collect::<Vec<_>>())
+                    .filter(|kv| kv.key.as_str() == "gen_ai.tool.name")
+                    .map(|kv| kv.value.to_string())
+                    .collect(),
+                _ => Vec::new(),
+            })
+            .collect();
+        let mut expected = vec![UNKNOWN_TOOL.to_string(), tool_name.to_string()];
+        labels.sort();
+        expected.sort();
+        assert_eq!(labels, expected);
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_tool_call() {
+        let wasm = create_wasm(add_two_wat());
+        let runtime = build_runtime(wasm.path()).await;
+        let server = build_test_server(&runtime);
+        let tool_name = server.tools.current().tools.keys().next().unwrap().clone();
+
+        let ct = CancellationToken::new();
+        ct.cancel();
+        let result = server
+            .handle_tool_call(
+                &server.tools.current(),
+                &tool_name,
+                &args!({"x": 5}),
+                None,
+                &ct,
+            )
+            .await;
+        assert_eq!(result.unwrap_err(), Interrupted::Cancelled);
+
+        let result = server
+            .handle_tool_call(
+                &server.tools.current(),
+                &tool_name,
+                &args!({"x": 5}),
+                None,
+                &CancellationToken::new(),
+            )
+            .await
+            .unwrap();
+        assert!(!result.is_error.unwrap_or(false));
+    }
+
+    // Publishes requests that never receive a reply.
+    struct SilentPublisher;
+
+    impl MessagePublisher for SilentPublisher {
+        fn publish_request(
+            &self,
+            _channel: &str,
+            _body: Vec<u8>,
+            _headers: HashMap<String, String>,
+        ) -> std::pin::Pin<
+            Box<dyn Future<Output = anyhow::Result<composable_runtime::ReturnAddress>> + Send + '_>,
+        > {
+            Box::pin(std::future::pending())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_channel_reply_timeout() {
+        use opentelemetry_sdk::trace::InMemorySpanExporter;
+
+        tokio::time::pause();
+        let exporter = InMemorySpanExporter::default();
+        let tracer_provider = SdkTracerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+        let wasm = create_wasm(add_two_wat());
+        let runtime = build_runtime(wasm.path()).await;
+        let input_schema = serde_json::json!({ "type": "object" });
+        let timeout = Duration::from_secs(30);
+        let tools = BTreeMap::from([(
+            "report".to_string(),
+            ResolvedTool {
+                tool: Tool::new("report", "", input_schema.as_object().unwrap().clone()),
+                input_validator: jsonschema::validator_for(&input_schema).unwrap(),
+                output_validator: None,
+                target: ResolvedToolTarget::Channel {
+                    channel: "reports".to_string(),
+                },
+                required_scopes: Vec::new(),
+                timeout: Some(timeout),
+                rate_limit: None,
+            },
+        )]);
+        let server = McpServer::new(
+            tools,
+            runtime.invoker(),
+            Some(Arc::new(SilentPublisher)),
+            "127.0.0.1:0".parse().unwrap(),
+            OriginPolicy::AllowAll,
+            Some(tracer_provider),
+        );
+
+        let result = server
+            .handle_tool_call(
+                &server.tools.current(),
+                "report",
+                &args!({}),
+                None,
+                &CancellationToken::new(),
+            )
+            .await;
+        assert_eq!(result.unwrap_err(), Interrupted::Timeout(timeout));
+
+        let client = setup_test_client(server).await;
+        let result = client
+            .call_tool(CallToolRequestParams::new("report").with_arguments(args!({})))
+            .await
+            .unwrap();
+        assert!(result.is_error.unwrap_or(false));
+        assert_eq!(
+            result.content[0].as_text().unwrap().text,
+            "Tool 'report' timed out after 30s"
+        );
+
+        let spans = exporter.get_finished_spans().unwrap();
+        let span = spans
+            .iter()
+            .find(|span| span.name.starts_with("tools/call"))
+            .expect("no tools/call span");
+        assert!(
+            span.attributes
+                .contains(&KeyValue::new("error.type", "timeout")),
+            "unexpected attributes: {:?}",
+            span.attributes
+        );
+    }
+
+    // Reports each of `pages` pages through composable:mcp/progress, with the
+    // message "crawling", and returns the page count.
+    fn crawl_wat() -> &'static str {
+        r#"
+        (component
+            (import "composable:mcp/progress@0.2.0" (instance $progress
+                (export "report" (func
+                    (param "progress" f64)
+                    (param "total" (option f64))
+                    (param "message" (option string))
+                ))
+            ))
+            (core module $memory
+                (memory (export "memory") 1)
+                (data (i32.const 0) "crawling")
+            )
+            (core instance $memory (instantiate $memory))
+            (alias core export $memory "memory" (core memory $mem))
+            (alias export $progress "report" (func $report))
+            (core func $report (canon lower (func $report) (memory $mem)))
+            (core module $m
+                (import "progress" "report" (func $report (param f64 i32 f64 i32 i32 i32)))
+                (func $crawl (param $pages i32) (result i32)
+                    (local $page i32)
+                    (block $done
+                        (loop $next
+                            (br_if $done (i32.ge_u (local.get $page) (local.get $pages)))
+                            (local.set $page (i32.add (local.get $page) (i32.const 1)))
+                            (call $report
+                                (f64.convert_i32_u (local.get $page))
+                                (i32.const 1)
+                                (f64.convert_i32_u (local.get $pages))
+                                (i32.const 1)
+                                (i32.const 0)
+                                (i32.const 8))
+                            (br $next)
+                        )
+                    )
+                    local.get $pages
+                )
+                (export "crawl" (func $crawl))
+            )
+            (core instance $i (instantiate $m
+                (with "progress" (instance (export "report" (func $report))))
+            ))
+            (func $crawl (param "pages" u32) (result u32) (canon lift (core func $i "crawl")))
+            (export "crawl" (func $crawl))
+        )
+        "#
+    }
+
+    #[derive(Clone)]
+    struct ProgressClientHandler {
+        notifications: tokio::sync::mpsc::UnboundedSender<ProgressNotificationParam>,
+    }
+
+    impl ClientHandler for ProgressClientHandler {
+        fn get_info(&self) -> ClientInfo {
+            ClientInfo::default()
+        }
+
+        async fn on_progress(
+            &self,
+            params: ProgressNotificationParam,
+            _context: NotificationContext<RoleClient>,
+        ) {
+            let _ = self.notifications.send(params);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_component_progress_reaches_client() {
+        let wasm = create_wasm(crawl_wat());
+        let mut definition = Builder::new().suffix(".toml").tempfile().unwrap();
+        write!(
+            definition,
+            r#"
+            [capability.progress]
+            type = "{PROGRESS_CAPABILITY}"
+
+            [component.crawler]
+            uri = "{}"
+            imports = ["progress"]
+            "#,
+            wasm.path().display()
+        )
+        .unwrap();
+        let progress = ProgressReporter::default();
+        let runtime = Runtime::builder()
+            .from_paths(&[definition.path().to_path_buf()])
+            .with_host_extension_instance(PROGRESS_CAPABILITY, progress.clone())
+            .build()
+            .await
+            .unwrap();
+        let server = build_test_server(&runtime)
+            .with_progress(progress)
+            .for_session();
+
+        let (server_transport, client_transport) = tokio::io::duplex(4096);
+        let server_handle = tokio::spawn(async move {
+            server.serve(server_transport).await?.waiting().await?;
+            anyhow::Ok(())
+        });
+        let (notifications, mut received) = tokio::sync::mpsc::unbounded_channel();
+        let client = ProgressClientHandler { notifications }
+            .serve(client_transport)
+            .await
+            .unwrap();
+
+        let token = ProgressToken(NumberOrString::Number(1));
+        let mut request =
+            CallToolRequestParams::new("crawler.crawl").with_arguments(args!({"pages": 3}));
+        request.meta = Some(Meta::with_progress_token(token.clone()));
+        let result = client.call_tool(request).await.unwrap();
+        assert!(!result.is_error.unwrap_or(false), "{result:?}");
+
+        let mut pages = Vec::new();
+        for _ in 0..3 {
+            let notification = tokio::time::timeout(Duration::from_secs(5), received.recv())
+                .await
+                .expect("progress notification")
+                .unwrap();
+            assert_eq!(notification.progress_token, token);
+            assert_eq!(notification.total, Some(3.0));
+            assert_eq!(notification.message.as_deref(), Some("crawling"));
+            pages.push(notification.progress);
+        }
+        // Notifications may be handled out of order by the client.
+        pages.sort_by(f64::total_cmp);
+        assert_eq!(pages, [1.0, 2.0, 3.0]);
+
+        // Without a token the component's reports are dropped.
+        let request =
+            CallToolRequestParams::new("crawler.crawl").with_arguments(args!({"pages": 2}));
+        let result = client.call_tool(request).await.unwrap();
+        assert!(!result.is_error.unwrap_or(false));
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(received.try_recv().is_err());
+
+        client.cancel().await.unwrap();
+        server_handle.abort();
+    }
+
+    #[tokio::test]
+    async fn test_resource_read() {
+        let wasm = create_wasm(add_two_wat());
+        let runtime = build_runtime(wasm.path()).await;
+
+        let component = runtime.list_components(None).remove(0);
+        let function = component.functions.values().next().unwrap().clone();
+        let server = build_test_server(&runtime).with_resources(vec![ResolvedResource {
+            name: "sum".to_string(),
+            uri_template: crate::resource::UriTemplate::parse("sum://{x}/plus-two").unwrap(),
+            description: Some("x plus two".to_string()),
+            mime_type: None,
+            function: Box::new(function),
+            component_name: component.metadata.name.clone(),
+        }]);
+        assert!(server.get_info().capabilities.resources.is_some());
+
+        let client = setup_test_client(server).await;
+
+        let templates = client.list_resource_templates(None).await.unwrap();
+        assert_eq!(templates.resource_templates.len(), 1);
+        assert_eq!(
+            templates.resource_templates[0].uri_template,
+            "sum://{x}/plus-two"
+        );
+        assert!(
+            client
+                .list_resources(None)
+                .await
+                .unwrap()
+                .resources
+                .is_empty()
+        );
+
+        let result = client
+            .read_resource(ReadResourceRequestParams::new("sum://5/plus-two"))
+            .await
+            .unwrap();
+        match &result.contents[0] {
+            ResourceContents::TextResourceContents {
+                text, mime_type, ..
+            } => {
+                assert_eq!(text.trim(), "7");
+                assert_eq!(mime_type.as_deref(), Some("application/json"));
+            }
+            other => panic!("expected text contents, got {other:?}"),
+        }
+
+        let missing = client
+            .read_resource(ReadResourceRequestParams::new("other://5"))
+            .await;
+        assert!(missing.is_err());
+    }
+
+    #[test]
+    fn test_byte_list_becomes_blob() {
+        let contents = result_to_resource_contents(
+            "img://chart",
+            Some("image/png"),
+            serde_json::json!([137, 80, 78, 71]),
+        );
+        match contents {
+            ResourceContents::BlobResourceContents {
+                blob, mime_type, ..
+            } => {
+                assert_eq!(blob, "iVBORw==");
+                assert_eq!(mime_type.as_deref(), Some("image/png"));
+            }
+            other => panic!("expected blob contents, got {other:?}"),
+        }
+    }
+
+    fn greet_wat() -> &'static str {
+        r#"
+        (component
+            (core module $m
+                (memory (export "memory") 1)
+                (data (i32.const 0) "\08\00\00\00\0e\00\00\00Hello, prompt!")
+                (func (export "greet") (result i32) i32.const 0)
+            )
+            (core instance $i (instantiate $m))
+            (func $f (result string) (canon lift (core func $i "greet") (memory $i "memory")))
+            (export "greet" (func $f))
+        )
+        "#
+    }
+
+    #[tokio::test]
+    async fn test_prompt_get() {
+        let wasm = create_wasm(greet_wat());
+        let runtime = build_runtime(wasm.path()).await;
+
+        let component = runtime.list_components(None).remove(0);
+        let function = component.functions.values().next().unwrap().clone();
+        let prompt = McpMapper::function_to_prompt(&function, "greeting", Some("Say hello"));
+        let server = build_test_server(&runtime).with_prompts(vec![ResolvedPrompt {
+            prompt,
+            function: Box::new(function),
+            component_name: component.metadata.name.clone(),
+        }]);
+        assert!(server.get_info().capabilities.prompts.is_some());
+
+        let client = setup_test_client(server).await;
+
+        let prompts = client.list_prompts(None).await.unwrap();
+        assert_eq!(prompts.prompts.len(), 1);
+        assert_eq!(prompts.prompts[0].name, "greeting");
+
+        let result = client
+            .get_prompt(GetPromptRequestParams::new("greeting"))
+            .await
+            .unwrap();
+        assert_eq!(result.description.as_deref(), Some("Say hello"));
+        assert_eq!(result.messages.len(), 1);
+        assert_eq!(result.messages[0].role, PromptMessageRole::User);
+        assert_eq!(
+            result.messages[0].content,
+            PromptMessageContent::text("Hello, prompt!")
+        );
+
+        let missing = client
+            .get_prompt(GetPromptRequestParams::new("nonexistent"))
+            .await;
+        assert!(missing.is_err());
+    }
+
+    #[test]
+    fn test_message_list_becomes_prompt_messages() {
+        let messages = result_to_prompt_messages(serde_json::json!([
+            { "role": "user", "content": "Review this diff" },
+            { "role": "assistant", "content": "Looking now" }
+        ]))
+        .unwrap();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[1].role, PromptMessageRole::Assistant);
+
+        let invalid = result_to_prompt_messages(serde_json::json!([{ "role": "system" }]));
+        assert!(invalid.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_tools_pagination() {
+        let wasm = create_wasm(add_two_wat());
+        let runtime = build_runtime(wasm.path()).await;
+        let server = build_test_server(&runtime).with_page_size(Some(2));
+
+        // Register the same function under several names.
+        let resolved = server
+            .tools
+            .current()
+            .tools
+            .values()
+            .next()
+            .unwrap()
+            .clone();
+        let mut tools: BTreeMap<String, ResolvedTool> = ["c", "a", "d", "b"]
+            .into_iter()
+            .map(|name| {
+                let mut tool = resolved.clone();
+                tool.tool.name = name.to_string().into();
+                (name.to_string(), tool)
+            })
+            .collect();
+        assert!(server.tools.swap(tools.clone()));
+
+        let (page, cursor) = server.tools_page(None).unwrap();
+        assert_eq!(page.len(), 2);
+        let cursor = cursor.expect("first page should have a next cursor");
+
+        let (page, next) = server.tools_page(Some(&cursor)).unwrap();
+        assert_eq!(page.len(), 2);
+        assert!(next.is_none());
+
+        assert!(server.tools_page(Some("bogus")).is_err());
+
+        // A cursor from a different snapshot is rejected.
+        tools.remove("a");
+        assert!(server.tools.swap(tools.clone()));
+        assert!(server.tools_page(Some(&cursor)).is_err());
+        assert!(!server.tools.swap(tools));
+
+        let client = setup_test_client(server).await;
+        let first = client.list_tools(None).await.unwrap();
+        assert_eq!(first.tools.len(), 2);
+        assert!(first.next_cursor.is_some());
+
+        let names: Vec<String> = client
+            .list_all_tools()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|t| t.name.to_string())
+            .collect();
+        assert_eq!(names, ["b", "c", "d"]);
+    }
+
+    #[tokio::test]
+    async fn test_invoke_tool_without_session() {
+        let wasm = create_wasm(add_two_wat());
+        let runtime = build_runtime(wasm.path()).await;
+        let server = build_test_server(&runtime);
+        let tool_name = server.tools.current().tools.keys().next().unwrap().clone();
+
+        let result = server.invoke_tool(&tool_name, &args!({"x": 5})).await;
+        assert!(!result.is_error.unwrap_or(false));
+        assert_eq!(result.content[0].as_text().unwrap().text, "7");
+
+        let result = server.invoke_tool(&tool_name, &args!({"x": "five"})).await;
+        assert_eq!(result.is_error, Some(true));
+        let result = server.invoke_tool("missing", &args!({})).await;
+        assert_eq!(result.is_error, Some(true));
+    }
+
+    #[test]
+    fn test_tool_call_scopes() {
+        let body = serde_json::json!([
+            { "jsonrpc": "2.0", "id": 1, "method": "tools/call", "params": { "name": "delete-user" } },
+            { "jsonrpc": "2.0", "id": 2, "method": "tools/call", "params": { "name": "get-user" } },
+            { "jsonrpc": "2.0", "id": 3, "method": "tools/list" }
+        ]);
+        let called = called_tools(body.to_string().as_bytes());
+        assert_eq!(called, ["delete-user", "get-user"]);
+        assert!(called_tools(b"not json").is_empty());
+
+        let input_schema = serde_json::json!({ "type": "object" });
+        let tool = |name: &'static str, scopes: &[&str]| {
+            (
+                name.to_string(),
+                ResolvedTool {
+                    tool: Tool::new(name, "", input_schema.as_object().unwrap().clone()),
+                    input_validator: jsonschema::validator_for(&input_schema).unwrap(),
+                    output_validator: None,
+                    target: ResolvedToolTarget::Channel {
+                        channel: name.to_string(),
+                    },
+                    required_scopes: scopes.iter().map(|s| s.to_string()).collect(),
+                    timeout: None,
+                    rate_limit: None,
+                },
+            )
+        };
+        let tools = ToolsHandle::new(BTreeMap::from([
+            tool("delete-user", &["users:read", "users:write"]),
+            tool("get-user", &["users:read"]),
+        ]));
+        let granted = ["users:read".to_string()];
+        assert_eq!(
+            tools.missing_scopes(&called, &granted),
+            ["users:read", "users:write"]
+        );
+        assert!(
+            tools
+                .missing_scopes(&["get-user".to_string()], &granted)
+                .is_empty()
+        );
+
+        // Advertised scopes follow the current tools.
+        assert_eq!(tools.scopes_supported(), ["users:read", "users:write"]);
+        assert!(tools.swap(BTreeMap::from([tool("get-user", &["users:read"])])));
+        assert_eq!(tools.scopes_supported(), ["users:read"]);
+    }
+
+    #[tokio::test]
+    async fn test_scoped_request_body_limit() {
+        let response = buffer_body(axum::body::Body::from(vec![b' '; 11]), 10)
+            .await
+            .unwrap_err();
+        assert_eq!(response.status(), axum::http::StatusCode::PAYLOAD_TOO_LARGE);
+        let bytes = buffer_body(axum::body::Body::from("{}"), 10).await.unwrap();
+        assert_eq!(&bytes[..], b"{}");
+    }
+
+    #[tokio::test]
+    async fn test_readiness_needs_publisher_for_channel_tools() {
+        let wasm = create_wasm(add_two_wat());
+        let runtime = build_runtime(wasm.path()).await;
+        let server = build_test_server(&runtime).with_name("calculator");
+
+        let (ready, report) = server.readiness();
+        assert!(ready);
+        assert_eq!(
+            report,
+            serde_json::json!({
+                "status": "ready",
+                "server": "calculator",
+                "tools": 1,
+                "channelTools": 0,
+                "publisher": false,
+            })
+        );
+
+        let mut tools = BTreeMap::new();
+        let input_schema = serde_json::json!({ "type": "object" });
+        tools.insert(
+            "notify".to_string(),
+            ResolvedTool {
+                tool: Tool::new(
+                    "notify",
+                    "Send a notification",
+                    input_schema.as_object().unwrap().clone(),
+                ),
+                input_validator: jsonschema::validator_for(&input_schema).unwrap(),
+                output_validator: None,
+                target: ResolvedToolTarget::Channel {
+                    channel: "notifications".to_string(),
+                },
+                required_scopes: Vec::new(),
+                timeout: None,
+                rate_limit: None,
+            },
+        );
+        assert!(server.tools_handle().swap(tools));
+        let (ready, report) = server.readiness();
+        assert!(!ready);
+        assert_eq!(report["status"], "unavailable");
+        assert_eq!(report["channelTools"], 1);
+    }
+
+    #[test]
+    fn test_rate_limited_result() {
+        let result = rate_limited_result("report", Duration::from_millis(1500));
+        assert_eq!(result.is_error, Some(true));
+        let json = serde_json::to_value(&result).unwrap();
+        assert_eq!(json["_meta"]["retryAfterMs"], 1500);
+        assert_eq!(
+            json["content"][0]["text"],
+            "Rate limit exceeded for tool 'report'; retry after 1.5s"
+        );
+    }
+
+    fn search_wat() -> &'static str {
+        // `search: func(passengers: option<party>) -> u32` with the WIT doc
+        // comments of the function and of `party.adults` in package-docs.
+        r#"
+        (component
+            (type $party (record (field "adults" u8) (field "children" u8)))
+            (export $party-type "party" (type $party))
+            (core module $m
+                (func (export "search") (param i32 i32 i32) (result i32)
+                    local.get 1)
+            )
+            (core instance $i (instantiate $m))
+            (func $f (param "passengers" (option $party-type)) (result u32)
+                (canon lift (core func $i "search")))
+            (export "search" (func $f))
+            (@custom "package-docs" "\00{\"worlds\":{\"root\":{\"funcs\":{\"search\":\"Search for flights.\"},\"types\":{\"party\":{\"items\":{\"adults\":\"Number of adults aged 16 or over\"}}}}}}")
+        )
+        "#
+    }
+
+    #[tokio::test]
+    async fn test_record_field_docs_from_wit() {
+        let wasm = create_wasm(search_wat());
+        let runtime = build_runtime(wasm.path()).await;
+        let server = build_test_server(&runtime);
+        let tool = server
+            .tools
+            .current()
+            .tools
+            .values()
+            .next()
+            .unwrap()
+            .tool
+            .clone();
+
+        assert_eq!(tool.description.as_deref(), Some("Search for flights."));
+        let passengers = &tool.input_schema["properties"]["passengers"];
+        assert_eq!(
+            passengers["properties"]["adults"]["description"],
+            "Number of adults aged 16 or over"
+        );
+        assert!(
+            passengers["properties"]["children"]
+                .get("description")
+                .is_none()
+        );
+    }
+
+    fn lookup_wat() -> &'static str {
+        // `lookup: func(id: u32) -> result<u32, lookup-error>` that always
+        // returns `err(not-found(7))`, laid out in memory at offset 0.
+        r#"
+        (component
+            (type $error (variant (case "not-found" u32) (case "invalid")))
+            (export $lookup-error "lookup-error" (type $error))
+            (core module $m
+                (memory (export "memory") 1)
+                (data (i32.const 0) "\01\00\00\00\00\00\00\00\07\00\00\00")
+                (func (export "lookup") (param i32) (result i32) i32.const 0)
+            )
+            (core instance $i (instantiate $m))
+            (func $f (param "id" u32) (result (result u32 (error $lookup-error)))
+                (canon lift (core func $i "lookup") (memory $i "memory")))
+            (export "lookup" (func $f))
+        )
+        "#
+    }
+
+    #[tokio::test]
+    async fn test_variant_error_is_structured() {
+        let wasm = create_wasm(lookup_wat());
+        let runtime = build_runtime(wasm.path()).await;
+        let server = build_test_server(&runtime);
+        let tool = server
+            .tools
+            .current()
+            .tools
+            .values()
+            .next()
+            .unwrap()
+            .tool
+            .clone();
+        let error_schema = tool
+            .meta
+            .as_ref()
+            .and_then(|meta| meta.get(ERROR_SCHEMA_META_KEY))
+            .expect("tool publishes an error schema")
+            .clone();
+
+        let result = server.invoke_tool(&tool.name, &args!({"id": 7})).await;
+        assert_eq!(result.is_error, Some(true));
+        let structured = result.structured_content.expect("structured error");
+        assert!(
+            jsonschema::validator_for(&error_schema)
+                .unwrap()
+                .is_valid(&structured),
+            "{structured} does not match {error_schema}"
+        );
+        assert!(structured["error"].to_string().contains("not-found"));
+    }
+
+    #[test]
+    fn test_content_blocks() {
+        let content = result_to_content(serde_json::json!([
+            { "type": "text", "value": "Weekly sales" },
+            { "type": "image", "value": { "data": [137, 80, 78, 71], "mime-type": "image/png" } },
+            { "type": "audio", "value": { "data": [82, 73], "mime-type": "audio/wav" } },
+            {
+                "type": "resource-link",
+                "value": { "uri": "file:///sales.csv", "name": "sales.csv", "mime-type": "text/csv" }
+            },
+            {
+                "type": "resource",
+                "value": {
+                    "uri": "file:///notes.md",
+                    "contents": { "type": "text", "value": "# Notes" }
+                }
+            }
+        ]))
+        .unwrap();
+        assert_eq!(content.len(), 5);
+        assert_eq!(content[0].as_text().unwrap().text, "Weekly sales");
+        let image = content[1].as_image().unwrap();
+        assert_eq!(image.data, "iVBORw==");
+        assert_eq!(image.mime_type, "image/png");
+        assert!(matches!(&content[2].raw, RawContent::Audio(a) if a.data == "Ukk="));
+        let link = content[3].as_resource_link().unwrap();
+        assert_eq!(link.name, "sales.csv");
+        assert_eq!(link.mime_type.as_deref(), Some("text/csv"));
+        assert!(matches!(
+            &content[4].as_resource().unwrap().resource,
+            ResourceContents::TextResourceContents { text, .. } if text == "# Notes"
+        ));
+
+        let err = result_to_content(serde_json::json!({
+            "type": "image",
+            "value": { "data": [300], "mime-type": "image/png" }
+        }))
+        .unwrap_err();
+        assert_eq!(err, "binary data must be a list of bytes");
+
+        let err =
+            result_to_content(serde_json::json!({ "type": "text", "value": 42 })).unwrap_err();
+        assert_eq!(err, "text content must be a string");
+        let err = result_to_content(serde_json::json!({
+            "type": "resource",
+            "value": { "uri": "file:///notes.md", "contents": { "type": "text" } }
+        }))
+        .unwrap_err();
+        assert_eq!(err, "text resource contents must be a string");
+    }
 }
@@ -1,26 +1,98 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::net::SocketAddr;
+use std::path::PathBuf;
 use std::pin::Pin;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use anyhow::Result;
-use composable_runtime::{ComponentInvoker, ConfigHandler, Function, MessagePublisher, Service};
-use rmcp::model::Tool;
+use composable_runtime::{
+    ComponentInvoker, ConfigHandler, Function, MessagePublisher, Runtime, Service,
+};
+use rmcp::model::{Prompt, Tool};
 use tokio::sync::watch;
 use tokio::task::JoinHandle;
 
-use crate::config::{self, McpServerConfig, McpServerConfigHandler, SharedConfig, ToolTarget};
+use crate::audit::{AuditLog, AuditWriter};
+use crate::auth::AuthPolicy;
+use crate::config::{
+    self, CheckingConfigHandler, ConfigErrors, McpServerConfig, McpServerConfigHandler,
+    RateLimitConfig, SharedConfig, ToolAnnotationsConfig, ToolConfig, ToolTarget, Transport,
+};
 use crate::mapper::McpMapper;
+use crate::metrics::PrometheusEndpoint;
 use crate::origin::OriginPolicy;
+use crate::progress::{PROGRESS_CAPABILITY, ProgressReporter};
+use crate::reload;
+use crate::resource::UriTemplate;
 use crate::server::McpServer;
 
+// How long shutdown waits for audit logs to finish writing.
+const AUDIT_FLUSH_TIMEOUT: Duration = Duration::from_secs(5);
+
 pub struct McpService {
     config: SharedConfig,
+    // Set when checking definitions, to collect invalid servers instead of failing.
+    config_errors: Option<ConfigErrors>,
     invoker: Mutex<Option<Arc<dyn ComponentInvoker>>>,
     publisher: Mutex<Option<Arc<dyn MessagePublisher>>>,
+    // Definition files to poll for tool changes.
+    watched_definitions: Option<Vec<PathBuf>>,
+    progress: ProgressReporter,
     shutdown_tx: watch::Sender<bool>,
     shutdown_rx: watch::Receiver<bool>,
     tasks: Mutex<Vec<JoinHandle<()>>>,
+    // Finished on shutdown, once the servers holding the logs have stopped.
+    audit_writers: Mutex<Vec<AuditWriter>>,
+}
+
+impl McpService {
+    /// Reload tools from these definition files whenever they change.
+    pub fn with_watched_definitions(mut self, paths: Vec<PathBuf>) -> Self {
+        self.watched_definitions = Some(paths);
+        self
+    }
+
+    /// Handle for forwarding `composable:mcp/progress` reports to clients.
+    pub fn progress_reporter(&self) -> ProgressReporter {
+        self.progress.clone()
+    }
+
+    /// Build a runtime from definition files without starting any service,
+    /// returning it with the MCP server definitions it loaded.
+    pub(crate) async fn load_definitions(
+        paths: &[PathBuf],
+    ) -> Result<(Runtime, Vec<McpServerConfig>)> {
+        Self::default().build_runtime(paths).await
+    }
+
+    /// Like `load_definitions`, but invalid server definitions are skipped and
+    /// their errors returned, rather than failing on the first one.
+    pub(crate) async fn check_definitions(
+        paths: &[PathBuf],
+    ) -> Result<(Runtime, Vec<McpServerConfig>, Vec<anyhow::Error>)> {
+        let errors = ConfigErrors::default();
+        let service = Self {
+            config_errors: Some(Arc::clone(&errors)),
+            ..Self::default()
+        };
+        let (runtime, server_configs) = service.build_runtime(paths).await?;
+        let errors = std::mem::take(&mut *errors.lock().unwrap());
+        Ok((runtime, server_configs, errors))
+    }
+
+    async fn build_runtime(self, paths: &[PathBuf]) -> Result<(Runtime, Vec<McpServerConfig>)> {
+        let config = Arc::clone(&self.config);
+        let progress = self.progress_reporter();
+        let runtime = Runtime::builder()
+            .from_paths(paths)
+            .with_host_extension_instance(PROGRESS_CAPABILITY, progress)
+            .with_service_instance(self)
+            .build()
+            .await?;
+        let server_configs = std::mem::take(&mut *config.lock().unwrap());
+        Ok((runtime, server_configs))
+    }
 }
 
 impl Default for McpService {
@@ -28,11 +100,15 @@ impl Default for McpService {
         let (shutdown_tx, shutdown_rx) = watch::channel(false);
         Self {
             config: config::shared_config(),
+            config_errors: None,
             invoker: Mutex::new(None),
             publisher: Mutex::new(None),
+            watched_definitions: None,
+            progress: ProgressReporter::default(),
             shutdown_tx,
             shutdown_rx,
             tasks: Mutex::new(Vec::new()),
+            audit_writers: Mutex::new(Vec::new()),
         }
     }
 }
@@ -43,6 +119,10 @@ pub enum ResolvedToolTarget {
     Component {
         function: Box<Function>,
         component_name: String,
+        /// Merged into the caller's arguments for omitted parameters.
+        defaults: serde_json::Map<String, serde_json::Value>,
+        /// Override any caller-supplied value.
+        fixed: serde_json::Map<String, serde_json::Value>,
     },
     Channel {
         channel: String,
@@ -56,148 +136,488 @@ pub struct ResolvedTool {
     pub input_validator: jsonschema::Validator,
     pub output_validator: Option<jsonschema::Validator>,
     pub target: ResolvedToolTarget,
+    /// OAuth scopes the caller's access token must carry to call this tool.
+    pub required_scopes: Vec<String>,
+    /// Deadline for the invocation or channel reply. `None` waits indefinitely.
+    pub timeout: Option<Duration>,
+    /// Calls allowed per session. `None` is unlimited.
+    pub rate_limit: Option<RateLimitConfig>,
+}
+
+/// A resolved resource: URI template + backing component function.
+#[derive(Clone)]
+pub struct ResolvedResource {
+    pub name: String,
+    pub uri_template: UriTemplate,
+    pub description: Option<String>,
+    pub mime_type: Option<String>,
+    pub function: Box<Function>,
+    pub component_name: String,
+}
+
+/// A resolved prompt: MCP prompt definition + backing component function.
+#[derive(Clone)]
+pub struct ResolvedPrompt {
+    pub prompt: Prompt,
+    pub function: Box<Function>,
+    pub component_name: String,
 }
 
 // Resolve all tools for a server from both explicit tool configs and component-selector.
-fn resolve_tools(
+pub(crate) fn resolve_tools(
     server_config: &McpServerConfig,
     invoker: &dyn ComponentInvoker,
-) -> Result<HashMap<String, ResolvedTool>> {
-    let mut tools = HashMap::new();
-
+) -> Result<BTreeMap<String, ResolvedTool>> {
     // Selector-discovered tools first (explicit tools take precedence on collision)
+    let mut tools = discover_tools(server_config, invoker)?;
+    for tool_config in &server_config.tools {
+        let (name, entry) = resolve_tool(server_config, tool_config, invoker)?;
+        tools.insert(name, entry);
+    }
+    Ok(tools)
+}
+
+/// Check every tool of a server, returning all errors rather than the first.
+pub(crate) fn check_tools(
+    server_config: &McpServerConfig,
+    invoker: &dyn ComponentInvoker,
+) -> Vec<anyhow::Error> {
+    let (_, mut errors) = discover_each_tool(server_config, invoker);
+    errors.extend(
+        server_config
+            .tools
+            .iter()
+            .filter_map(|tool_config| resolve_tool(server_config, tool_config, invoker).err()),
+    );
+    errors
+}
+
+// Resolve the tools matched by the server's component-selector, if any.
+fn discover_tools(
+    server_config: &McpServerConfig,
+    invoker: &dyn ComponentInvoker,
+) -> Result<BTreeMap<String, ResolvedTool>> {
+    let (tools, errors) = discover_each_tool(server_config, invoker);
+    match errors.into_iter().next() {
+        Some(e) => Err(e),
+        None => Ok(tools),
+    }
+}
+
+// Discover the selector's tools, skipping and collecting an error for each
+// component or tool that cannot be resolved.
+fn discover_each_tool(
+    server_config: &McpServerConfig,
+    invoker: &dyn ComponentInvoker,
+) -> (BTreeMap<String, ResolvedTool>, Vec<anyhow::Error>) {
+    let mut tools = BTreeMap::new();
+    let mut errors = Vec::new();
     if let Some(selector) = &server_config.component_selector {
         let components = invoker.list_components(Some(selector));
+        // Unsanitized `component.function` of each discovered tool, by name.
+        let mut discovered: HashMap<String, String> = HashMap::new();
         for component in components {
+            let annotations = match ToolAnnotationsConfig::from_labels(
+                &component.metadata.name,
+                &component.metadata.labels,
+            ) {
+                Ok(annotations) => annotations,
+                Err(e) => {
+                    errors.push(e);
+                    continue;
+                }
+            };
             for function in component.functions.values() {
-                let tool_name = format!("{}.{}", component.metadata.name, function.key());
-                let tool = McpMapper::function_to_tool(function, &tool_name, None);
+                let qualified_name = format!("{}.{}", component.metadata.name, function.key());
+                let tool_name = match &server_config.tool_naming {
+                    Some(naming) => {
+                        McpMapper::tool_name(naming, &component.metadata.name, &function.key())
+                    }
+                    None => qualified_name.clone(),
+                };
+                if let Some(other) = discovered.insert(tool_name.clone(), qualified_name.clone()) {
+                    errors.push(anyhow::anyhow!(
+                        "Server '{}': tools '{other}' and '{qualified_name}' are both named \
+                         '{tool_name}'; adjust 'tool-naming' to tell them apart",
+                        server_config.name
+                    ));
+                    continue;
+                }
+                let mut tool = McpMapper::function_to_tool(function, &tool_name, None);
+                tool.annotations = annotations.as_ref().map(McpMapper::tool_annotations);
                 let input_schema = serde_json::Value::Object((*tool.input_schema).clone());
-                let input_validator = build_validator(
+                let input_validator = match build_validator(
                     &server_config.name,
                     &tool_name,
                     "input-schema",
                     &input_schema,
-                )?;
+                ) {
+                    Ok(validator) => validator,
+                    Err(e) => {
+                        errors.push(e);
+                        continue;
+                    }
+                };
                 let target = ResolvedToolTarget::Component {
                     function: Box::new(function.clone()),
                     component_name: component.metadata.name.clone(),
+                    defaults: serde_json::Map::new(),
+                    fixed: serde_json::Map::new(),
                 };
                 tools.insert(
                     tool_name,
                     ResolvedTool {
                         tool,
                         input_validator,
-                        // Currently a WIT-derived schema. Will add validator
-                        // when config allows optional explicit output-schema.
+                        // The WIT-derived schema admits every result the
+                        // function can return, so there is nothing to validate.
                         output_validator: None,
                         target,
+                        required_scopes: Vec::new(),
+                        timeout: server_config.timeout,
+                        rate_limit: server_config.rate_limit,
                     },
                 );
             }
         }
     }
 
-    // Explicit tool configs override selector-discovered tools on name collision
-    for tool_config in &server_config.tools {
-        let (name, entry) = match &tool_config.target {
-            ToolTarget::Component {
-                component,
-                function,
-            } => {
-                let comp = invoker.get_component(component).ok_or_else(|| {
+    (tools, errors)
+}
+
+// Resolve an explicitly configured tool.
+fn resolve_tool(
+    server_config: &McpServerConfig,
+    tool_config: &ToolConfig,
+    invoker: &dyn ComponentInvoker,
+) -> Result<(String, ResolvedTool)> {
+    let entry = match &tool_config.target {
+        ToolTarget::Component {
+            component,
+            function,
+            output_schema,
+            defaults,
+            fixed,
+        } => {
+            let comp = invoker.get_component(component).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Server '{}': tool '{}' references unknown component '{}'",
+                    server_config.name,
+                    tool_config.name,
+                    component,
+                )
+            })?;
+            let func = comp.functions.get(function).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Tool '{}': function '{}' not found in component '{}'",
+                    tool_config.name,
+                    function,
+                    component,
+                )
+            })?;
+            let mut tool = McpMapper::function_to_tool(
+                func,
+                &tool_config.name,
+                tool_config.description.as_deref(),
+            );
+            // Configured annotations take precedence over component labels.
+            let label_annotations =
+                ToolAnnotationsConfig::from_labels(&comp.metadata.name, &comp.metadata.labels)?;
+            let annotations = match (tool_config.annotations.clone(), label_annotations) {
+                (Some(configured), Some(labeled)) => Some(configured.or(labeled)),
+                (configured, labeled) => configured.or(labeled),
+            };
+            if let Some(annotations) = &annotations {
+                annotations.validate().map_err(|e| {
                     anyhow::anyhow!(
-                        "Server '{}': tool '{}' references unknown component '{}'",
+                        "Server '{}': tool '{}' {e}",
                         server_config.name,
-                        tool_config.name,
-                        component,
+                        tool_config.name
                     )
                 })?;
-                let func = comp.functions.get(function).ok_or_else(|| {
-                    anyhow::anyhow!(
-                        "Tool '{}': function '{}' not found in component '{}'",
-                        tool_config.name,
-                        function,
-                        component,
-                    )
-                })?;
-                let tool = McpMapper::function_to_tool(
-                    func,
-                    &tool_config.name,
-                    tool_config.description.as_deref(),
-                );
-                let input_schema = serde_json::Value::Object((*tool.input_schema).clone());
-                let input_validator = build_validator(
-                    &server_config.name,
-                    &tool_config.name,
-                    "input-schema",
-                    &input_schema,
-                )?;
-                let target = ResolvedToolTarget::Component {
-                    function: Box::new(func.clone()),
-                    component_name: comp.metadata.name.clone(),
-                };
-                (
-                    tool_config.name.clone(),
-                    ResolvedTool {
-                        tool,
-                        input_validator,
-                        // Currently a WIT-derived schema. Will add validator
-                        // when config allows optional explicit output-schema.
-                        output_validator: None,
-                        target,
-                    },
-                )
+                tool.annotations = Some(McpMapper::tool_annotations(annotations));
             }
-            ToolTarget::Channel {
-                channel,
-                input_schema,
-                output_schema,
-            } => {
-                let tool = McpMapper::channel_tool(
-                    &tool_config.name,
-                    tool_config.description.as_deref(),
-                    input_schema.clone(),
-                    output_schema.clone(),
-                );
-                let input_schema_val = serde_json::Value::Object((*tool.input_schema).clone());
-                let input_validator = build_validator(
-                    &server_config.name,
-                    &tool_config.name,
-                    "input-schema",
-                    &input_schema_val,
-                )?;
-                let output_validator = tool
-                    .output_schema
-                    .as_ref()
-                    .map(|s| {
-                        let schema = serde_json::Value::Object((**s).clone());
-                        build_validator(
-                            &server_config.name,
-                            &tool_config.name,
-                            "output-schema",
-                            &schema,
+            check_argument_overrides(
+                &server_config.name,
+                &tool_config.name,
+                func,
+                defaults.iter().chain(fixed.iter()),
+            )?;
+            let mut input_schema = (*tool.input_schema).clone();
+            McpMapper::apply_argument_overrides(&mut input_schema, defaults, fixed);
+            tool.input_schema = Arc::new(input_schema);
+            let input_schema = serde_json::Value::Object((*tool.input_schema).clone());
+            let input_validator = build_validator(
+                &server_config.name,
+                &tool_config.name,
+                "input-schema",
+                &input_schema,
+            )?;
+            let output_validator = output_schema
+                .as_ref()
+                .map(|explicit| {
+                    let derived = tool.output_schema.as_ref().ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "Server '{}': tool '{}' has 'output-schema' but function '{}' \
+                             does not return a structured result",
+                            server_config.name,
+                            tool_config.name,
+                            function,
                         )
-                    })
-                    .transpose()?;
-                let target = ResolvedToolTarget::Channel {
-                    channel: channel.clone(),
-                };
-                (
-                    tool_config.name.clone(),
-                    ResolvedTool {
-                        tool,
-                        input_validator,
-                        output_validator,
-                        target,
-                    },
+                    })?;
+                    let derived = serde_json::Value::Object((**derived).clone());
+                    McpMapper::check_output_schema(explicit, &derived).map_err(|e| {
+                        anyhow::anyhow!(
+                            "Server '{}': tool '{}' {e}",
+                            server_config.name,
+                            tool_config.name
+                        )
+                    })?;
+                    let validator = build_validator(
+                        &server_config.name,
+                        &tool_config.name,
+                        "output-schema",
+                        explicit,
+                    )?;
+                    let schema = explicit.as_object().ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "Server '{}': tool '{}' 'output-schema' must be a table",
+                            server_config.name,
+                            tool_config.name
+                        )
+                    })?;
+                    tool.output_schema = Some(Arc::new(schema.clone()));
+                    Ok::<_, anyhow::Error>(validator)
+                })
+                .transpose()?;
+            let target = ResolvedToolTarget::Component {
+                function: Box::new(func.clone()),
+                component_name: comp.metadata.name.clone(),
+                defaults: defaults.clone(),
+                fixed: fixed.clone(),
+            };
+            (
+                tool_config.name.clone(),
+                ResolvedTool {
+                    tool,
+                    input_validator,
+                    output_validator,
+                    target,
+                    required_scopes: tool_config.scopes.clone(),
+                    timeout: tool_config.timeout.or(server_config.timeout),
+                    rate_limit: tool_config.rate_limit.or(server_config.rate_limit),
+                },
+            )
+        }
+        ToolTarget::Channel {
+            channel,
+            input_schema,
+            output_schema,
+        } => {
+            let mut tool = McpMapper::channel_tool(
+                &tool_config.name,
+                tool_config.description.as_deref(),
+                input_schema.clone(),
+                output_schema.clone(),
+            );
+            tool.annotations = tool_config
+                .annotations
+                .as_ref()
+                .map(McpMapper::tool_annotations);
+            let input_schema_val = serde_json::Value::Object((*tool.input_schema).clone());
+            let input_validator = build_validator(
+                &server_config.name,
+                &tool_config.name,
+                "input-schema",
+                &input_schema_val,
+            )?;
+            let output_validator = tool
+                .output_schema
+                .as_ref()
+                .map(|s| {
+                    let schema = serde_json::Value::Object((**s).clone());
+                    build_validator(
+                        &server_config.name,
+                        &tool_config.name,
+                        "output-schema",
+                        &schema,
+                    )
+                })
+                .transpose()?;
+            let target = ResolvedToolTarget::Channel {
+                channel: channel.clone(),
+            };
+            (
+                tool_config.name.clone(),
+                ResolvedTool {
+                    tool,
+                    input_validator,
+                    output_validator,
+                    target,
+                    required_scopes: tool_config.scopes.clone(),
+                    timeout: tool_config.timeout.or(server_config.timeout),
+                    rate_limit: tool_config.rate_limit.or(server_config.rate_limit),
+                },
+            )
+        }
+    };
+    Ok(entry)
+}
+
+// Resolve all resources for a server, checking URI template variables against function params.
+pub(crate) fn resolve_resources(
+    server_config: &McpServerConfig,
+    invoker: &dyn ComponentInvoker,
+) -> Result<Vec<ResolvedResource>> {
+    let mut resources = Vec::new();
+    for resource_config in &server_config.resources {
+        let comp = invoker
+            .get_component(&resource_config.component)
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Server '{}': resource '{}' references unknown component '{}'",
+                    server_config.name,
+                    resource_config.name,
+                    resource_config.component,
+                )
+            })?;
+        let func = comp
+            .functions
+            .get(&resource_config.function)
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Resource '{}': function '{}' not found in component '{}'",
+                    resource_config.name,
+                    resource_config.function,
+                    resource_config.component,
                 )
+            })?;
+        let uri_template = UriTemplate::parse(&resource_config.uri).map_err(|e| {
+            anyhow::anyhow!(
+                "Server '{}': resource '{}' has invalid uri: {e}",
+                server_config.name,
+                resource_config.name,
+            )
+        })?;
+
+        let variables: Vec<&str> = uri_template.variables().collect();
+        for variable in &variables {
+            if !func.params().iter().any(|p| p.name == *variable) {
+                return Err(anyhow::anyhow!(
+                    "Server '{}': resource '{}' uri variable '{variable}' is not a parameter of function '{}'",
+                    server_config.name,
+                    resource_config.name,
+                    resource_config.function,
+                ));
             }
-        };
-        tools.insert(name, entry);
+        }
+        for param in func.params() {
+            if !param.is_optional && !variables.contains(&param.name.as_str()) {
+                return Err(anyhow::anyhow!(
+                    "Server '{}': resource '{}' uri does not bind required parameter '{}' of function '{}'",
+                    server_config.name,
+                    resource_config.name,
+                    param.name,
+                    resource_config.function,
+                ));
+            }
+        }
+
+        resources.push(ResolvedResource {
+            name: resource_config.name.clone(),
+            uri_template,
+            description: resource_config.description.clone(),
+            mime_type: resource_config.mime_type.clone(),
+            function: Box::new(func.clone()),
+            component_name: comp.metadata.name.clone(),
+        });
     }
+    Ok(resources)
+}
 
-    Ok(tools)
+// Resolve all prompts for a server. Functions must return a string or a list of messages.
+pub(crate) fn resolve_prompts(
+    server_config: &McpServerConfig,
+    invoker: &dyn ComponentInvoker,
+) -> Result<Vec<ResolvedPrompt>> {
+    let mut prompts = Vec::new();
+    for prompt_config in &server_config.prompts {
+        let comp = invoker
+            .get_component(&prompt_config.component)
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Server '{}': prompt '{}' references unknown component '{}'",
+                    server_config.name,
+                    prompt_config.name,
+                    prompt_config.component,
+                )
+            })?;
+        let func = comp.functions.get(&prompt_config.function).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Prompt '{}': function '{}' not found in component '{}'",
+                prompt_config.name,
+                prompt_config.function,
+                prompt_config.component,
+            )
+        })?;
+        let result_type = func
+            .result()
+            .and_then(|r| r.get("type"))
+            .and_then(|t| t.as_str());
+        if !matches!(result_type, Some("string") | Some("array")) {
+            return Err(anyhow::anyhow!(
+                "Server '{}': prompt '{}' function '{}' must return a string or a list of messages",
+                server_config.name,
+                prompt_config.name,
+                prompt_config.function,
+            ));
+        }
+        let prompt = McpMapper::function_to_prompt(
+            func,
+            &prompt_config.name,
+            prompt_config.description.as_deref(),
+        );
+        prompts.push(ResolvedPrompt {
+            prompt,
+            function: Box::new(func.clone()),
+            component_name: comp.metadata.name.clone(),
+        });
+    }
+    Ok(prompts)
+}
+
+// Check that each default or fixed argument names a parameter and matches its schema.
+fn check_argument_overrides<'a>(
+    server_name: &str,
+    tool_name: &str,
+    function: &Function,
+    overrides: impl Iterator<Item = (&'a String, &'a serde_json::Value)>,
+) -> Result<()> {
+    for (name, value) in overrides {
+        let param = function
+            .params()
+            .iter()
+            .find(|p| &p.name == name)
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Server '{server_name}': tool '{tool_name}' sets argument '{name}' \
+                     but function '{}' has no such parameter",
+                    function.function_name()
+                )
+            })?;
+        let validator = build_validator(
+            server_name,
+            tool_name,
+            "parameter schema",
+            &param.json_schema,
+        )?;
+        if let Err(error) = validator.validate(value) {
+            return Err(anyhow::anyhow!(
+                "Server '{server_name}': tool '{tool_name}' argument '{name}' is invalid: {error}"
+            ));
+        }
+    }
+    Ok(())
 }
 
 fn build_validator(
@@ -213,9 +633,12 @@ fn build_validator(
 
 impl Service for McpService {
     fn config_handler(&self) -> Option<Box<dyn ConfigHandler>> {
-        Some(Box::new(McpServerConfigHandler::new(Arc::clone(
-            &self.config,
-        ))))
+        let servers = Arc::clone(&self.config);
+        let handler: Box<dyn ConfigHandler> = match &self.config_errors {
+            Some(errors) => Box::new(CheckingConfigHandler::new(servers, Arc::clone(errors))),
+            None => Box::new(McpServerConfigHandler::new(servers)),
+        };
+        Some(handler)
     }
 
     fn set_invoker(&self, invoker: Arc<dyn ComponentInvoker>) {
@@ -249,10 +672,24 @@ impl Service for McpService {
             server_configs.push(config::default_server());
         }
 
+        let stdio_servers = server_configs
+            .iter()
+            .filter(|s| s.transport == Transport::Stdio)
+            .count();
+        if stdio_servers > 1 {
+            return Err(anyhow::anyhow!(
+                "Only one MCP server can use transport 'stdio', found {stdio_servers}"
+            ));
+        }
+
+        let watched = self.watched_definitions.as_ref();
         let mut handles = Vec::new();
+        let mut tools_handles = Vec::new();
 
         for server_config in server_configs {
             let tools = resolve_tools(&server_config, &*invoker)?;
+            let resources = resolve_resources(&server_config, &*invoker)?;
+            let prompts = resolve_prompts(&server_config, &*invoker)?;
 
             let tool_count = tools.len();
             let origin_policy = OriginPolicy::from_config(
@@ -271,6 +708,12 @@ impl Service for McpService {
                     )
                 })?;
 
+            let auth_policy = server_config
+                .auth
+                .as_ref()
+                .map(|auth| AuthPolicy::from_config(&server_config.name, auth))
+                .transpose()?;
+
             let tracer_provider = server_config
                 .otlp_endpoint
                 .as_deref()
@@ -282,6 +725,27 @@ impl Service for McpService {
                     )
                 })
                 .transpose()?;
+            let audit = match &server_config.audit {
+                Some(audit) => {
+                    let (log, writer) = AuditLog::open(&server_config.name, audit)?;
+                    self.audit_writers.lock().unwrap().push(writer);
+                    Some(log)
+                }
+                None => None,
+            };
+
+            let prometheus = server_config
+                .prometheus
+                .as_ref()
+                .map(PrometheusEndpoint::new);
+            let meter_provider = crate::metrics::build_meter_provider(
+                server_config
+                    .otlp_endpoint
+                    .as_deref()
+                    .map(|ep| (ep, server_config.otlp_protocol.as_str())),
+                prometheus.as_ref(),
+                &server_config.name,
+            )?;
 
             let server = McpServer::new(
                 tools,
@@ -290,7 +754,20 @@ impl Service for McpService {
                 addr,
                 origin_policy,
                 tracer_provider,
-            );
+            )
+            .with_name(&server_config.name)
+            .with_transport(server_config.transport)
+            .with_health(server_config.health.clone())
+            .with_audit(audit)
+            .with_auth(auth_policy)
+            .with_metrics(meter_provider)
+            .with_prometheus(prometheus)
+            .with_page_size(server_config.page_size)
+            .with_resources(resources)
+            .with_prompts(prompts)
+            .with_progress(self.progress.clone())
+            .with_tools_list_changed(watched.is_some());
+            tools_handles.push((server_config.name.clone(), server.tools_handle()));
 
             tracing::info!(
                 server_name = server_config.name,
@@ -306,6 +783,15 @@ impl Service for McpService {
             }));
         }
 
+        if let Some(paths) = watched {
+            handles.push(tokio::spawn(reload::watch_definitions(
+                paths.clone(),
+                tools_handles,
+                Arc::clone(&invoker),
+                self.shutdown_rx.clone(),
+            )));
+        }
+
         *self.tasks.lock().unwrap() = handles;
         Ok(())
     }
@@ -320,6 +806,114 @@ impl Service for McpService {
             for handle in handles {
                 let _ = handle.await;
             }
+            // Stopped servers have dropped their logs; sessions still ending may
+            // hold a clone for a moment longer.
+            let writers = std::mem::take(&mut *self.audit_writers.lock().unwrap());
+            for writer in writers {
+                if tokio::time::timeout(AUDIT_FLUSH_TIMEOUT, writer.finish())
+                    .await
+                    .is_err()
+                {
+                    tracing::warn!(
+                        "Audit log still in use at shutdown; its last records may be lost"
+                    );
+                }
+            }
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write as _;
+
+    fn definition(content: &str) -> tempfile::NamedTempFile {
+        let mut f = tempfile::Builder::new().suffix(".toml").tempfile().unwrap();
+        f.write_all(content.as_bytes()).unwrap();
+        f
+    }
+
+    #[tokio::test]
+    async fn test_load_merges_server_tables() {
+        let base = definition(
+            r#"
+            [server.mcp]
+            type = "mcp"
+            port = 3001
+
+            [server.mcp.tool.adder]
+            component = "calculator"
+            function = "add"
+            "#,
+        );
+        let overlay = definition(
+            r#"
+            [server.mcp]
+            page-size = 10
+            "#,
+        );
+
+        let (_, configs) = McpService::load_definitions(&[
+            base.path().to_path_buf(),
+            overlay.path().to_path_buf(),
+        ])
+        .await
+        .unwrap();
+        assert_eq!(configs.len(), 1);
+        assert_eq!(configs[0].name, "mcp");
+        assert_eq!(configs[0].page_size, Some(10));
+        assert_eq!(configs[0].tools.len(), 1);
+        assert_eq!(configs[0].tools[0].name, "adder");
+    }
+
+    #[tokio::test]
+    async fn test_load_reports_invalid_server() {
+        let file = definition(
+            r#"
+            [server.mcp]
+            type = "mcp"
+            port = "not-a-port"
+            component-selector = "!dependents"
+            "#,
+        );
+        let err = McpService::load_definitions(&[file.path().to_path_buf()])
+            .await
+            .unwrap_err();
+        assert!(
+            format!("{err:#}").contains("'port' must be a number"),
+            "unexpected error: {err:#}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_check_reports_every_invalid_server() {
+        let file = definition(
+            r#"
+            [server.first]
+            type = "mcp"
+            port = "not-a-port"
+            component-selector = "!dependents"
+
+            [server.second]
+            type = "mcp"
+            port = 3002
+            page-size = 0
+            component-selector = "!dependents"
+
+            [server.third]
+            type = "mcp"
+            port = 3003
+            component-selector = "!dependents"
+            "#,
+        );
+        let (_, configs, errors) = McpService::check_definitions(&[file.path().to_path_buf()])
+            .await
+            .unwrap();
+        assert_eq!(configs.len(), 1);
+        assert_eq!(configs[0].name, "third");
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].to_string().contains("Server 'first'"));
+        assert!(errors[1].to_string().contains("Server 'second'"));
+    }
+}